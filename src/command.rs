@@ -1,12 +1,30 @@
 use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec, string::String};
+use crate::{Justification, Error};
 
 pub use self::charset::Charset;
 pub use self::font::Font;
 pub use self::code_table::CodeTable;
+pub use self::hri_position::HriPosition;
+pub use self::error_correction::ErrorCorrection;
+pub use self::qr_model::QrModel;
+pub use self::user_char_bitmap::UserCharBitmap;
+pub use self::print_color::PrintColor;
 
 mod charset;
 mod code_table;
 mod font;
+mod hri_position;
+mod error_correction;
+mod qr_model;
+mod user_char_bitmap;
+mod print_color;
+
+// Compares two names ignoring the case, and the separators between words
+fn same_name(a: &str, b: &str) -> bool {
+    let normalized = |name: &str| name.chars().filter(|c| !matches!(c, '_' | '-' | ' ')).flat_map(char::to_lowercase).collect::<String>();
+    normalized(a) == normalized(b)
+}
 
 /// Common commands usefull for the printer
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -17,11 +35,29 @@ pub enum Command {
     Reset,
     /// Print mode selected to reset the fonts. Equivalent to ESC ! 0
     PrintModeDefault,
+    /// Turns condensed printing on (SI) or off (DC2)
+    ///
+    /// Independent of the font selected with ESC M. Condensed characters are narrower, so more columns fit in a line, but the [Formatter](crate::Formatter) width is not adjusted.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x0f], Command::Condensed{on: true}.as_bytes());
+    /// assert_eq!(vec![0x12], Command::Condensed{on: false}.as_bytes());
+    /// ```
+    Condensed {
+        on: bool
+    },
     /// Set an international character set, Equivalent to ESC R
     SelectCharset {
         /// Character set to be set
         charset: Charset
     },
+    /// Initializes the printer, clearing the buffer and restoring the default settings. Equivalent to ESC @
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1b, 0x40], Command::Init.as_bytes());
+    /// ```
+    Init,
     /// Selects a different code table, Equivalent to ESC t
     SelectCodeTable {
         code_table: CodeTable
@@ -40,16 +76,280 @@ pub enum Command {
     Bitmap,
     /// Change line size
     NoLine,
-    ResetLine
+    ResetLine,
+    /// Prints the buffer and feeds the given number of lines. Equivalent to ESC d
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1b, 0x64, 0x00], Command::FeedLines{lines: 0}.as_bytes());
+    /// assert_eq!(vec![0x1b, 0x64, 0x03], Command::FeedLines{lines: 3}.as_bytes());
+    /// assert_eq!(vec![0x1b, 0x64, 0xff], Command::FeedLines{lines: 255}.as_bytes());
+    /// ```
+    FeedLines {
+        lines: u8
+    },
+    /// Prints the buffer and feeds the paper back the given number of lines. Equivalent to ESC e
+    ///
+    /// Most printers limit how far the paper can go back, larger values get clamped by the printer.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1b, 0x65, 0x02], Command::ReverseFeedLines{lines: 2}.as_bytes());
+    /// ```
+    ReverseFeedLines {
+        lines: u8
+    },
+    /// Feeds the paper up to the next black mark, where printing starts. Equivalent to GS FF
+    ///
+    /// Only for printers with a black mark sensor, with the sensor enabled.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x0c], Command::FeedToMark.as_bytes());
+    /// ```
+    FeedToMark,
+    /// Moves the printed paper out of the presenter, by the given number of steps. Equivalent to GS e with n = 3
+    ///
+    /// Only for printers with a presenter (mostly kiosk printers), the meaning of the steps depends on the model.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x65, 0x03, 0x28], Command::PresentPaper{length: 40}.as_bytes());
+    /// ```
+    PresentPaper {
+        length: u8
+    },
+    /// Pulls back the paper held by the presenter. Equivalent to GS e with n = 2
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x65, 0x02], Command::RetractPaper.as_bytes());
+    /// ```
+    RetractPaper,
+    /// Discards the data of the current page, in page mode. Equivalent to CAN
+    ///
+    /// Ignored in standard mode.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x18], Command::Cancel.as_bytes());
+    /// ```
+    Cancel,
+    /// Clears the receive and print buffers, in real time. Equivalent to DLE DC4 with function 8
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x10, 0x14, 0x08, 0x01, 0x03, 0x14, 0x01, 0x06, 0x02, 0x08], Command::ClearBuffers.as_bytes());
+    /// ```
+    ClearBuffers,
+    /// Recovers the printer from a recoverable error, in real time. Equivalent to DLE ENQ
+    ///
+    /// The printer restarts printing from the line where the error occurred, unless the buffers get cleared.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x10, 0x05, 0x01], Command::Recover{clear_buffers: false}.as_bytes());
+    /// assert_eq!(vec![0x10, 0x05, 0x02], Command::Recover{clear_buffers: true}.as_bytes());
+    /// ```
+    Recover {
+        clear_buffers: bool
+    },
+    /// Downloads a user-defined character into a code point, from 32 to 126. Equivalent to ESC &
+    ///
+    /// The character only gets printed instead of the font's one after [EnableUserChars](Command::EnableUserChars). See [UserCharBitmap](crate::command::UserCharBitmap) for the cell size of each font.
+    /// ```rust
+    /// use escpos_rs::command::{Command, UserCharBitmap};
+    /// let bitmap = UserCharBitmap::new(3, vec![0xff; 36]);
+    /// let bytes = Command::DefineUserChar{code: b'$', bitmap}.as_bytes();
+    /// // Bytes per column, first and last code, and the width of the character
+    /// assert_eq!(vec![0x1b, 0x26, 0x03, b'$', b'$', 0x0c], bytes[..6].to_vec());
+    /// assert_eq!(vec![0xff; 36], bytes[6..].to_vec());
+    /// # #[cfg(feature = "std")]
+    /// assert_eq!(Ok(()), escpos_rs::validate_escpos(&bytes));
+    /// ```
+    DefineUserChar {
+        code: u8,
+        bitmap: UserCharBitmap
+    },
+    /// Selects if user-defined characters replace the font's ones. Equivalent to ESC %
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1b, 0x25, 0x01], Command::EnableUserChars{on: true}.as_bytes());
+    /// assert_eq!(vec![0x1b, 0x25, 0x00], Command::EnableUserChars{on: false}.as_bytes());
+    /// ```
+    EnableUserChars {
+        on: bool
+    },
+    /// Asks the printer to send back a status byte, in real time. Equivalent to DLE EOT
+    ///
+    /// The kind of status goes from 1 to 4: printer, offline cause, error cause and paper roll sensor. The answer arrives once everything sent before got processed.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x10, 0x04, 0x01], Command::TransmitStatus{status: 1}.as_bytes());
+    /// ```
+    ///
+    /// The variant does not check the kind of status, see [transmit_status](Command::transmit_status) for a checked version.
+    TransmitStatus {
+        status: u8
+    },
+    /// Sets the justification for the following content. Equivalent to ESC a
+    SelectJustification {
+        justification: Justification
+    },
+    /// Stores and prints a QR code with the printer's own generator. Equivalent to the GS ( k function sequence
+    ///
+    /// Not all printers support this command, check the printer's specsheet. When deserializing, the model defaults to 2, the module size to 6 dots and the error correction to M.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::{Command, ErrorCorrection, QrModel};
+    /// let qr_code = |model| Command::QrCode{content: "hi".into(), model, module_size: 6, error_correction: ErrorCorrection::M}.as_bytes();
+    /// // The model gets selected first
+    /// assert!(qr_code(QrModel::Model1).starts_with(&[0x1d, 0x28, 0x6b, 0x04, 0x00, 0x31, 0x41, 0x31, 0x00]));
+    /// assert!(qr_code(QrModel::Model2).starts_with(&[0x1d, 0x28, 0x6b, 0x04, 0x00, 0x31, 0x41, 0x32, 0x00]));
+    /// assert!(qr_code(QrModel::Micro).starts_with(&[0x1d, 0x28, 0x6b, 0x04, 0x00, 0x31, 0x41, 0x33, 0x00]));
+    /// ```
+    QrCode {
+        content: String,
+        /// Model of the code
+        #[serde(default)]
+        model: QrModel,
+        /// Size, in dots, of each module of the code (from 1 to 16)
+        #[serde(default = "default_qr_module_size")]
+        module_size: u8,
+        /// Error correction level
+        #[serde(default)]
+        error_correction: ErrorCorrection
+    },
+    /// Sets the width and height multipliers of the characters, from 1 to 8. Equivalent to GS !
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x21, 0x00], Command::SelectCharacterSize{width: 1, height: 1}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x21, 0x11], Command::SelectCharacterSize{width: 2, height: 2}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x21, 0x70], Command::SelectCharacterSize{width: 8, height: 1}.as_bytes());
+    /// ```
+    SelectCharacterSize {
+        width: u8,
+        height: u8
+    },
+    /// Sets the horizontal tab stops, as columns in ascending order. Equivalent to ESC D
+    ///
+    /// A tab character (`\t`) in the text moves the head to the next stop. Up to 32 stops are supported by most printers, and an empty list clears them.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1b, 0x44, 0x08, 0x10, 0x18, 0x00], Command::SetTabStops{positions: vec![8, 16, 24]}.as_bytes());
+    /// assert_eq!(vec![0x1b, 0x44, 0x00], Command::SetTabStops{positions: vec![]}.as_bytes());
+    /// ```
+    SetTabStops {
+        positions: Vec<u8>
+    },
+    /// Turns reverse video (white text over black background) on or off. Equivalent to GS B
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x42, 0x01], Command::ReverseVideo{on: true}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x42, 0x00], Command::ReverseVideo{on: false}.as_bytes());
+    ///
+    /// // Survives a serialization round trip, like the rest of the commands
+    /// let serialized = serde_json::to_string(&Command::ReverseVideo{on: true}).unwrap();
+    /// let command: Command = serde_json::from_str(&serialized).unwrap();
+    /// assert_eq!(vec![0x1d, 0x42, 0x01], command.as_bytes());
+    /// ```
+    ReverseVideo {
+        on: bool
+    },
+    /// Selects where the human readable digits of barcodes get printed. Equivalent to GS H
+    ///
+    /// ```rust
+    /// use escpos_rs::command::{Command, HriPosition};
+    /// assert_eq!(vec![0x1d, 0x48, 0x02], Command::SelectHriPosition{position: HriPosition::default()}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x48, 0x00], Command::SelectHriPosition{position: HriPosition::None}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x48, 0x03], Command::SelectHriPosition{position: HriPosition::Both}.as_bytes());
+    /// ```
+    SelectHriPosition {
+        position: HriPosition
+    },
+    /// Selects the color of the characters, on two-color printers. Equivalent to GS ( N, with function 48
+    ///
+    /// Older models that do not understand this command use ESC r instead, which can be sent as raw bytes.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::{Command, PrintColor};
+    /// assert_eq!(vec![0x1d, 0x28, 0x4e, 0x02, 0x00, 0x30, 0x31], Command::TextColor{color: PrintColor::Black}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x28, 0x4e, 0x02, 0x00, 0x30, 0x32], Command::TextColor{color: PrintColor::Red}.as_bytes());
+    /// ```
+    TextColor {
+        color: PrintColor
+    },
+    /// Selects the font for the human readable digits of barcodes. Equivalent to GS f
+    ///
+    /// Most printers only support fonts A and B here.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::{Command, Font};
+    /// assert_eq!(vec![0x1d, 0x66, 0x01], Command::SelectHriFont{font: Font::FontB}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x66, 0x00], Command::SelectHriFont{font: Font::FontA}.as_bytes());
+    /// ```
+    SelectHriFont {
+        font: Font
+    },
+    /// Enters or leaves the user setting mode, required to change memory switches. Equivalent to GS ( E with functions 1 and 2
+    ///
+    /// Leaving the mode makes the printer restart, to apply the new settings.
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(vec![0x1d, 0x28, 0x45, 0x03, 0x00, 0x01, 0x49, 0x4e], Command::UserSettingMode{enter: true}.as_bytes());
+    /// assert_eq!(vec![0x1d, 0x28, 0x45, 0x04, 0x00, 0x02, 0x4f, 0x55, 0x54], Command::UserSettingMode{enter: false}.as_bytes());
+    /// ```
+    UserSettingMode {
+        enter: bool
+    },
+    /// Writes a memory switch, only works in user setting mode. Equivalent to GS ( E with function 3
+    ///
+    /// The memory switches live in non-volatile memory, so the values survive a power cycle. Each bit of the value sets one bit of the switch, the least significant one being bit 1. See the printer's manual for the meaning of each bit, and [Printer::unsafe_set_memory_switch](crate::Printer::unsafe_set_memory_switch).
+    /// ```rust
+    /// use escpos_rs::command::Command;
+    /// assert_eq!(
+    ///     vec![0x1d, 0x28, 0x45, 0x0a, 0x00, 0x03, 0x02, b'1', b'0', b'0', b'0', b'0', b'0', b'0', b'1'],
+    ///     Command::MemorySwitch{switch: 2, value: 0b1000_0001}.as_bytes()
+    /// );
+    /// // The digits go from bit 8 to bit 1
+    /// let mut target = vec![0x1d, 0x28, 0x45, 0x0a, 0x00, 0x03, 0x01];
+    /// target.extend_from_slice(b"00000001");
+    /// assert_eq!(target, Command::MemorySwitch{switch: 1, value: 0b0000_0001}.as_bytes());
+    /// ```
+    MemorySwitch {
+        switch: u8,
+        value: u8
+    }
 }
 
 impl Command {
+    /// Creates a [TransmitStatus](Command::TransmitStatus) command, checking the kind of status
+    ///
+    /// Only the kinds 1 to 4 exist, anything else gives back an [InvalidStatusRequest](crate::Error::InvalidStatusRequest) error. The answer can be read with the [Printer](crate::Printer)'s [read](crate::Printer::read) method, after sending the command with [raw](crate::Printer::raw).
+    /// ```rust
+    /// use escpos_rs::{Error, command::Command};
+    /// for status in 1..=4 {
+    ///     assert_eq!(vec![0x10, 0x04, status], Command::transmit_status(status)?.as_bytes());
+    /// }
+    /// assert!(matches!(Command::transmit_status(0), Err(Error::InvalidStatusRequest(0))));
+    /// assert!(matches!(Command::transmit_status(5), Err(Error::InvalidStatusRequest(5))));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn transmit_status(status: u8) -> Result<Command, Error> {
+        if (1..=4).contains(&status) {
+            Ok(Command::TransmitStatus{status})
+        } else {
+            Err(Error::InvalidStatusRequest(status))
+        }
+    }
+
     /// Returns the byte-array representation of each command
     pub fn as_bytes(&self) -> Vec<u8> {
         match self {
             Command::Cut => vec![0x1d, 0x56, 0x41, 0x96],
             Command::Reset => vec![0x1d, 0x40],
+            Command::Init => vec![0x1b, 0x40],
             Command::PrintModeDefault => vec![0x01b, 0x21, 0x00],
+            Command::Condensed{on} => vec![if *on { 0x0f } else { 0x12 }],
             Command::SelectCharset{charset} => {
                 let mut res = vec![0x1b, 0x52];
                 res.append(&mut charset.as_bytes());
@@ -72,7 +372,88 @@ impl Command {
             Command::BoldOff => vec![0x1b, 0x45, 0x00],
             Command::Bitmap => vec![0x1b, 0x2a],
             Command::NoLine => vec![0x1b, 0x33, 0x00],
-            Command::ResetLine => vec![0x1b, 0x32]
+            Command::ResetLine => vec![0x1b, 0x32],
+            Command::FeedLines{lines} => vec![0x1b, 0x64, *lines],
+            Command::ReverseFeedLines{lines} => vec![0x1b, 0x65, *lines],
+            Command::FeedToMark => vec![0x1d, 0x0c],
+            Command::PresentPaper{length} => vec![0x1d, 0x65, 0x03, *length],
+            Command::RetractPaper => vec![0x1d, 0x65, 0x02],
+            Command::Cancel => vec![0x18],
+            Command::ClearBuffers => vec![0x10, 0x14, 0x08, 0x01, 0x03, 0x14, 0x01, 0x06, 0x02, 0x08],
+            Command::Recover{clear_buffers} => vec![0x10, 0x05, if *clear_buffers { 0x02 } else { 0x01 }],
+            Command::TransmitStatus{status} => vec![0x10, 0x04, *status],
+            Command::DefineUserChar{code, bitmap} => {
+                let mut bytes = vec![0x1b, 0x26, bitmap.height(), *code, *code, bitmap.width()];
+                bytes.extend_from_slice(bitmap.data());
+                bytes
+            },
+            Command::EnableUserChars{on} => vec![0x1b, 0x25, if *on { 0x01 } else { 0x00 }],
+            Command::SelectJustification{justification} => vec![0x1b, 0x61, match justification {
+                Justification::Left => 0x00,
+                Justification::Center => 0x01,
+                Justification::Right => 0x02
+            }],
+            Command::QrCode{content, model, module_size, error_correction} => {
+                // Model selection
+                let mut res = vec![0x1d, 0x28, 0x6b, 0x04, 0x00, 0x31, 0x41];
+                res.append(&mut model.as_bytes());
+                res.push(0x00);
+                // Module size
+                res.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x43, *module_size]);
+                // Error correction level
+                res.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x45]);
+                res.append(&mut error_correction.as_bytes());
+                // Data storage, the length counts the 3 bytes of the function
+                let length = content.len() + 3;
+                res.extend_from_slice(&[0x1d, 0x28, 0x6b, (length % 256) as u8, (length / 256) as u8, 0x31, 0x50, 0x30]);
+                res.extend_from_slice(content.as_bytes());
+                // Print the stored data
+                res.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x51, 0x30]);
+                res
+            },
+            Command::SelectCharacterSize{width, height} => {
+                let width = width.clamp(&1, &8) - 1;
+                let height = height.clamp(&1, &8) - 1;
+                vec![0x1d, 0x21, (width << 4) | height]
+            },
+            Command::SetTabStops{positions} => {
+                let mut res = vec![0x1b, 0x44];
+                res.extend_from_slice(positions);
+                res.push(0x00);
+                res
+            },
+            Command::ReverseVideo{on} => vec![0x1d, 0x42, *on as u8],
+            Command::SelectHriPosition{position} => {
+                let mut res = vec![0x1d, 0x48];
+                res.append(&mut position.as_bytes());
+                res
+            },
+            Command::TextColor{color} => {
+                let mut res = vec![0x1d, 0x28, 0x4e, 0x02, 0x00, 0x30];
+                res.append(&mut color.as_bytes());
+                res
+            },
+            Command::SelectHriFont{font} => {
+                let mut res = vec![0x1d, 0x66];
+                res.append(&mut font.as_bytes());
+                res
+            },
+            Command::UserSettingMode{enter} => if *enter {
+                vec![0x1d, 0x28, 0x45, 0x03, 0x00, 0x01, 0x49, 0x4e]
+            } else {
+                vec![0x1d, 0x28, 0x45, 0x04, 0x00, 0x02, 0x4f, 0x55, 0x54]
+            },
+            Command::MemorySwitch{switch, value} => {
+                let mut res = vec![0x1d, 0x28, 0x45, 0x0a, 0x00, 0x03, *switch];
+                // One ascii digit per bit, from bit 8 to bit 1
+                res.extend((0..8).rev().map(|bit| if value & (1 << bit) != 0 { b'1' } else { b'0' }));
+                res
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+// Module size for qr codes missing it when deserialized
+fn default_qr_module_size() -> u8 {
+    6
+}
@@ -1,9 +1,11 @@
 extern crate serde;
 
 use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec, string::ToString};
+use crate::Error;
 
 /// Possible character sets
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Charset {
     /// United states of america
     USA,
@@ -44,6 +46,47 @@ pub enum Charset {
 }
 
 impl Charset {
+    /// All the character sets
+    pub const ALL: &'static [Charset] = &[Charset::USA, Charset::France, Charset::Germany, Charset::UK, Charset::Denmark1, Charset::Sweden, Charset::Italy, Charset::Spain1, Charset::Japan, Charset::Norway, Charset::Denmark2, Charset::Spain2, Charset::LatinAmerica, Charset::Korea, Charset::SloveniaCroatia, Charset::China, Charset::Vietnam, Charset::Arabia];
+
+    /// Name of the character set, for configuration files
+    ///
+    /// Names are lowercase, with words separated by an underscore. They get parsed back with [FromStr](core::str::FromStr), ignoring the case and the separators.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Charset;
+    /// assert_eq!("latin_america", Charset::LatinAmerica.as_str());
+    /// for charset in Charset::ALL {
+    ///     assert_eq!(Some(charset), charset.as_str().parse::<Charset>().ok().as_ref());
+    /// }
+    /// assert_eq!(Charset::France, "France".parse()?);
+    /// assert_eq!(Charset::LatinAmerica, "LatinAmerica".parse()?);
+    /// assert!("atlantis".parse::<Charset>().is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Charset::USA => "usa",
+            Charset::France => "france",
+            Charset::Germany => "germany",
+            Charset::UK => "uk",
+            Charset::Denmark1 => "denmark1",
+            Charset::Sweden => "sweden",
+            Charset::Italy => "italy",
+            Charset::Spain1 => "spain1",
+            Charset::Japan => "japan",
+            Charset::Norway => "norway",
+            Charset::Denmark2 => "denmark2",
+            Charset::Spain2 => "spain2",
+            Charset::LatinAmerica => "latin_america",
+            Charset::Korea => "korea",
+            Charset::SloveniaCroatia => "slovenia_croatia",
+            Charset::China => "china",
+            Charset::Vietnam => "vietnam",
+            Charset::Arabia => "arabia"
+        }
+    }
+
     /// Returns the byte representation of the esc/pos command
     pub fn as_bytes(&self) -> Vec<u8> {
         match self {
@@ -67,4 +110,18 @@ impl Charset {
             Charset::Arabia => vec![0x11]
         }
     }
-}
\ No newline at end of file
+}
+
+impl core::str::FromStr for Charset {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Charset, Error> {
+        Charset::ALL.iter().find(|charset| super::same_name(charset.as_str(), name)).cloned().ok_or_else(|| Error::UnknownName(name.to_string()))
+    }
+}
+
+impl core::fmt::Display for Charset {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(formatter, "{}", self.as_str())
+    }
+}
@@ -1,20 +1,238 @@
 extern crate serde;
 
 use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec, string::ToString};
+use crate::Error;
+
+/// Full width katakana, sorted as in the katakana code page (starting from 0xa1)
+const KATAKANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+/// Characters of the CP437 code page, from 0x80 to 0xff
+const CP437: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+/// Look-alike characters that CP437 printers show with the glyph of another byte
+const CP437_VARIANTS: &[(char, u8)] = &[
+    ('Δ', 0x7f), ('β', 0xe1), ('Π', 0xe3), ('∏', 0xe3), ('∑', 0xe4), ('μ', 0xe6),
+    ('ð', 0xeb), ('∂', 0xeb), ('ϕ', 0xed), ('\u{1d719}', 0xed), ('∅', 0xed), ('⌀', 0xed),
+    ('Ø', 0xed), ('ø', 0xed), ('∈', 0xee), ('€', 0xee), ('✓', 0xfb)
+];
+/// Characters of the PC850 code page, from 0x80 to 0xff
+const PC850: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{ad}±‗¾¶§÷¸°¨·¹³²■\u{a0}";
+/// Characters of the PC852 code page, from 0x80 to 0xff
+const PC852: &str = "ÇüéâäůćçłëŐőîŹÄĆÉĹĺôöĽľŚśÖÜŤťŁ×čáíóúĄąŽžĘę¬źČş«»░▒▓│┤ÁÂĚŞ╣║╗╝Żż┐└┴┬├─┼Ăă╚╔╩╦╠═╬¤đĐĎËďŇÍÎě┘┌█▄ŢŮ▀ÓßÔŃńňŠšŔÚŕŰýÝţ´\u{ad}˝˛ˇ˘§÷¸°¨˙űŘř■\u{a0}";
+/// Katakana that get printed with a dakuten (voiced mark), and their base character
+const KATAKANA_DAKUTEN: &[(char, char)] = &[
+    ('ガ', 'カ'), ('ギ', 'キ'), ('グ', 'ク'), ('ゲ', 'ケ'), ('ゴ', 'コ'),
+    ('ザ', 'サ'), ('ジ', 'シ'), ('ズ', 'ス'), ('ゼ', 'セ'), ('ゾ', 'ソ'),
+    ('ダ', 'タ'), ('ヂ', 'チ'), ('ヅ', 'ツ'), ('デ', 'テ'), ('ド', 'ト'),
+    ('バ', 'ハ'), ('ビ', 'ヒ'), ('ブ', 'フ'), ('ベ', 'ヘ'), ('ボ', 'ホ'),
+    ('ヴ', 'ウ')
+];
+/// Katakana that get printed with a handakuten (semi-voiced mark), and their base character
+const KATAKANA_HANDAKUTEN: &[(char, char)] = &[
+    ('パ', 'ハ'), ('ピ', 'ヒ'), ('プ', 'フ'), ('ペ', 'ヘ'), ('ポ', 'ホ')
+];
 
 /// Possible character sets
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum CodeTable {
     USA,
-    Latin2
+    /// Half-width katakana, for japanese printers
+    Katakana,
+    /// Multilingual latin code page
+    PC850,
+    /// Central european latin code page (PC852)
+    Latin2,
+    /// Windows arabic code page
+    ///
+    /// Printers draw from left to right, so right-to-left text gets reordered to its visual order when the `rtl` feature is enabled. Without it, the text is sent in logical order. The letters are printed in their isolated form.
+    WPC1256
 }
 
 impl CodeTable {
+    /// All the code tables
+    pub const ALL: &'static [CodeTable] = &[CodeTable::USA, CodeTable::Katakana, CodeTable::PC850, CodeTable::Latin2, CodeTable::WPC1256];
+
+    /// Name of the code table, for configuration files
+    ///
+    /// Names get parsed back with [FromStr](core::str::FromStr), ignoring the case and the separators.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::CodeTable;
+    /// assert_eq!("pc850", CodeTable::PC850.as_str());
+    /// for code_table in CodeTable::ALL {
+    ///     assert_eq!(Some(code_table), code_table.as_str().parse::<CodeTable>().ok().as_ref());
+    /// }
+    /// assert_eq!(CodeTable::Katakana, "KATAKANA".parse()?);
+    /// assert!("ebcdic".parse::<CodeTable>().is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodeTable::USA => "usa",
+            CodeTable::Katakana => "katakana",
+            CodeTable::PC850 => "pc850",
+            CodeTable::Latin2 => "latin2",
+            CodeTable::WPC1256 => "wpc1256"
+        }
+    }
+
     /// Returns the byte representation of the esc/pos command
     pub fn as_bytes(&self) -> Vec<u8> {
         match self {
             CodeTable::USA => vec![0x00],
-            CodeTable::Latin2 => vec![0x02]
+            CodeTable::Katakana => vec![0x01],
+            CodeTable::PC850 => vec![0x02],
+            CodeTable::Latin2 => vec![0x12],
+            CodeTable::WPC1256 => vec![0x32]
+        }
+    }
+
+    /// Encodes an utf-8 string into the code table
+    ///
+    /// Code tables without a dedicated encoding fall back to CP437. An [Encoding](crate::Error::Encoding) error is returned if a character has no representation in the table.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::CodeTable;
+    /// assert_eq!(vec![0xb6, 0xc0, 0xb6, 0xc5], CodeTable::Katakana.encode("カタカナ")?);
+    /// // Voiced characters take two bytes
+    /// assert_eq!(vec![0xb6, 0xde, 0xd7, 0xbd], CodeTable::Katakana.encode("ガラス")?);
+    /// // PC850 places some characters where CP437 has box drawings
+    /// assert_eq!(vec![0x9b, 0xb5, 0xd2], CodeTable::PC850.encode("øÁÊ")?);
+    /// // Latin2 is PC852, with its own characters in the same spots
+    /// assert_eq!(vec![0x88, 0xb5, 0xa7], CodeTable::Latin2.encode("łÁž")?);
+    /// assert!(CodeTable::PC850.encode("ł").is_err());
+    /// assert!(CodeTable::USA.encode("Ê").is_err());
+    /// // Ascii content stays the same, with no additions
+    /// assert_eq!(b"Total:5.00".to_vec(), CodeTable::USA.encode("Total:5.00")?);
+    ///
+    /// // Arabic text gets reordered with the rtl feature, so it reads right when printed from left to right
+    /// let bytes = CodeTable::WPC1256.encode("سلام")?;
+    /// #[cfg(feature = "rtl")]
+    /// assert_eq!(vec![0xe3, 0xc7, 0xe1, 0xd3], bytes);
+    /// #[cfg(not(feature = "rtl"))]
+    /// assert_eq!(vec![0xd3, 0xe1, 0xc7, 0xe3], bytes);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn encode<A: AsRef<str>>(&self, content: A) -> Result<Vec<u8>, Error> {
+        match self {
+            CodeTable::Katakana => {
+                let mut result = Vec::new();
+                for c in content.as_ref().chars() {
+                    result.append(&mut encode_katakana(c).ok_or(Error::Encoding)?);
+                }
+                Ok(result)
+            },
+            CodeTable::PC850 => content.as_ref().chars().map(|c| encode_page(c, PC850).ok_or(Error::Encoding)).collect(),
+            CodeTable::Latin2 => content.as_ref().chars().map(|c| encode_page(c, PC852).ok_or(Error::Encoding)).collect(),
+            CodeTable::WPC1256 => {
+                let mut result = Vec::new();
+                for c in visual_order(content.as_ref()).chars() {
+                    result.push(encode_arabic(c).ok_or(Error::Encoding)?);
+                }
+                Ok(result)
+            },
+            _other => content.as_ref().chars().map(|c| encode_cp437(c).ok_or(Error::Encoding)).collect()
         }
     }
-}
\ No newline at end of file
+}
+
+impl core::str::FromStr for CodeTable {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<CodeTable, Error> {
+        CodeTable::ALL.iter().find(|code_table| super::same_name(code_table.as_str(), name)).cloned().ok_or_else(|| Error::UnknownName(name.to_string()))
+    }
+}
+
+impl core::fmt::Display for CodeTable {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(formatter, "{}", self.as_str())
+    }
+}
+
+// Auxiliar function to find the katakana page representation of a character
+fn encode_katakana(c: char) -> Option<Vec<u8>> {
+    match c {
+        // Ascii and control characters stay the same
+        '\u{00}'..='\u{7e}' => Some(vec![c as u8]),
+        // Half width katakana have a direct translation
+        '\u{ff61}'..='\u{ff9f}' => Some(vec![(c as u32 - 0xff61 + 0xa1) as u8]),
+        // Combining voiced marks
+        '\u{3099}' => Some(vec![0xde]),
+        '\u{309a}' => Some(vec![0xdf]),
+        c => {
+            if let Some(idx) = KATAKANA.chars().position(|k| k == c) {
+                Some(vec![0xa1 + idx as u8])
+            } else if let Some((_, base)) = KATAKANA_DAKUTEN.iter().find(|(k, _)| *k == c) {
+                let mut res = encode_katakana(*base)?;
+                res.push(0xde);
+                Some(res)
+            } else if let Some((_, base)) = KATAKANA_HANDAKUTEN.iter().find(|(k, _)| *k == c) {
+                let mut res = encode_katakana(*base)?;
+                res.push(0xdf);
+                Some(res)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+// Auxiliar function to find the representation of a character in a code page with ascii in its lower half
+fn encode_page(c: char, page: &str) -> Option<u8> {
+    match c {
+        '\u{00}'..='\u{7f}' => Some(c as u8),
+        c => page.chars().position(|p| p == c).map(|idx| 0x80 + idx as u8)
+    }
+}
+
+// Auxiliar function to find the CP437 representation of a character, with its look-alikes
+fn encode_cp437(c: char) -> Option<u8> {
+    encode_page(c, CP437).or_else(|| CP437_VARIANTS.iter().find(|(v, _)| *v == c).map(|(_, b)| *b))
+}
+
+// Auxiliar function to find the windows arabic code page representation of a character
+fn encode_arabic(c: char) -> Option<u8> {
+    match c {
+        '\u{00}'..='\u{7f}' => Some(c as u8),
+        '\u{060c}' => Some(0xa1),
+        '\u{061b}' => Some(0xba),
+        '\u{061f}' => Some(0xbf),
+        // From hamza to dad, contiguous
+        '\u{0621}'..='\u{0636}' => Some((c as u32 - 0x0621 + 0xc1) as u8),
+        // From tah to ghain, skipping the multiplication sign at 0xd7
+        '\u{0637}'..='\u{063a}' => Some((c as u32 - 0x0637 + 0xd8) as u8),
+        '\u{0640}' => Some(0xdc),
+        '\u{0641}'..='\u{0643}' => Some((c as u32 - 0x0641 + 0xdd) as u8),
+        '\u{0644}' => Some(0xe1),
+        '\u{0645}'..='\u{0648}' => Some((c as u32 - 0x0645 + 0xe3) as u8),
+        '\u{0649}' => Some(0xec),
+        '\u{064a}' => Some(0xed),
+        _other => None
+    }
+}
+
+// Auxiliar function to reorder each line from logical to visual order
+#[cfg(feature = "rtl")]
+fn visual_order(content: &str) -> alloc::string::String {
+    use alloc::format;
+    use unicode_bidi::BidiInfo;
+
+    // Lines get reordered one by one, so the line breaks stay in place. The surrounding spaces are kept too, as they come from the justification.
+    content.split('\n').map(|line| {
+        let text = line.trim_matches(' ');
+        let start = line.len() - line.trim_start_matches(' ').len();
+        let end = start + text.len();
+        let bidi_info = BidiInfo::new(text, None);
+        let text = match bidi_info.paragraphs.first() {
+            Some(paragraph) => bidi_info.reorder_line(paragraph, paragraph.range.clone()).into_owned(),
+            None => text.to_string()
+        };
+        format!("{}{}{}", &line[..start], text, &line[end..])
+    }).collect::<Vec<_>>().join("\n")
+}
+
+// Without the rtl feature, the text keeps its logical order
+#[cfg(not(feature = "rtl"))]
+fn visual_order(content: &str) -> alloc::borrow::Cow<'_, str> {
+    alloc::borrow::Cow::Borrowed(content)
+}
@@ -0,0 +1,32 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec};
+
+/// Error correction level of a QR code
+///
+/// Higher levels allow the code to be read even if part of it is damaged, at the cost of a bigger code.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% of the code
+    L,
+    /// Recovers about 15% of the code
+    #[default]
+    M,
+    /// Recovers about 25% of the code
+    Q,
+    /// Recovers about 30% of the code
+    H
+}
+
+impl ErrorCorrection {
+    /// Byte representation of each level, for the GS ( k function
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            ErrorCorrection::L => vec![0x30],
+            ErrorCorrection::M => vec![0x31],
+            ErrorCorrection::Q => vec![0x32],
+            ErrorCorrection::H => vec![0x33]
+        }
+    }
+}
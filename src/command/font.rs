@@ -1,6 +1,8 @@
 extern crate serde;
 
 use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec, string::ToString};
+use crate::Error;
 
 /// Common fonts used in thermal printers
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq)]
@@ -15,6 +17,33 @@ pub enum Font {
 impl Eq for Font{}
 
 impl Font {
+    /// All the fonts
+    pub const ALL: &'static [Font] = &[Font::FontA, Font::FontB, Font::FontC, Font::FontD, Font::FontE];
+
+    /// Name of the font, for configuration files
+    ///
+    /// Names get parsed back with [FromStr](core::str::FromStr), ignoring the case and the separators.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::Font;
+    /// assert_eq!("font_b", Font::FontB.as_str());
+    /// for font in Font::ALL {
+    ///     assert_eq!(Some(font), font.as_str().parse::<Font>().ok().as_ref());
+    /// }
+    /// assert_eq!(Font::FontA, "FontA".parse()?);
+    /// assert!("font_z".parse::<Font>().is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Font::FontA => "font_a",
+            Font::FontB => "font_b",
+            Font::FontC => "font_c",
+            Font::FontD => "font_d",
+            Font::FontE => "font_e"
+        }
+    }
+
     /// Byte representation of each font.
     pub fn as_bytes(&self) -> Vec<u8> {
         match self {
@@ -25,4 +54,18 @@ impl Font {
             Font::FontE => vec![0x04]
         }
     }
-}
\ No newline at end of file
+}
+
+impl core::str::FromStr for Font {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Font, Error> {
+        Font::ALL.iter().find(|font| super::same_name(font.as_str(), name)).cloned().ok_or_else(|| Error::UnknownName(name.to_string()))
+    }
+}
+
+impl core::fmt::Display for Font {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(formatter, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,30 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec};
+
+/// Position of the human readable interpretation (HRI) of a barcode
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum HriPosition {
+    /// The digits are not printed
+    None,
+    /// Above the barcode
+    Above,
+    /// Below the barcode
+    #[default]
+    Below,
+    /// Both above and below the barcode
+    Both
+}
+
+impl HriPosition {
+    /// Byte representation of each position
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            HriPosition::None => vec![0x00],
+            HriPosition::Above => vec![0x01],
+            HriPosition::Below => vec![0x02],
+            HriPosition::Both => vec![0x03]
+        }
+    }
+}
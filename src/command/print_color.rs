@@ -0,0 +1,24 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec};
+
+/// Color of the printed characters, for two-color printers
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum PrintColor {
+    /// First color of the ribbon or paper, usually black
+    #[default]
+    Black,
+    /// Second color of the ribbon or paper, usually red
+    Red
+}
+
+impl PrintColor {
+    /// Byte representation of each color, as the `n` parameter of `GS ( N` with function 48
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            PrintColor::Black => vec![0x31],
+            PrintColor::Red => vec![0x32]
+        }
+    }
+}
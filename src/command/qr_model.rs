@@ -0,0 +1,58 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use qrcode::{QrCode, EcLevel, Version};
+#[cfg(feature = "std")]
+use super::ErrorCorrection;
+
+/// Model of a natively printed QR code
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum QrModel {
+    /// Original model, needed by some legacy scanners
+    Model1,
+    /// Enhanced model, the most common one
+    #[default]
+    Model2,
+    /// Micro QR code, for really short contents
+    Micro
+}
+
+impl QrModel {
+    /// Byte representation of each model, for the GS ( k function
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            QrModel::Model1 => vec![0x31],
+            QrModel::Model2 => vec![0x32],
+            QrModel::Micro => vec![0x33]
+        }
+    }
+
+    /// Checks if the content fits in a code of this model, with the given error correction
+    ///
+    /// The check is done against the largest version of each model (40 for model 2, M4 for micro codes). Model 1 codes are checked as model 2 codes of version 14, its largest one, which is slightly optimistic. Only available with the `std` feature.
+    ///
+    /// ```rust
+    /// use escpos_rs::command::{QrModel, ErrorCorrection};
+    /// assert!(QrModel::Model2.fits("https://example.com", ErrorCorrection::M));
+    /// assert!(!QrModel::Model2.fits(&"a".repeat(3000), ErrorCorrection::L));
+    /// assert!(QrModel::Micro.fits("12345", ErrorCorrection::L));
+    /// assert!(!QrModel::Micro.fits("https://example.com", ErrorCorrection::L));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn fits<A: AsRef<[u8]>>(&self, content: A, error_correction: ErrorCorrection) -> bool {
+        let ec_level = match error_correction {
+            ErrorCorrection::L => EcLevel::L,
+            ErrorCorrection::M => EcLevel::M,
+            ErrorCorrection::Q => EcLevel::Q,
+            ErrorCorrection::H => EcLevel::H
+        };
+        let version = match self {
+            QrModel::Model1 => Version::Normal(14),
+            QrModel::Model2 => Version::Normal(40),
+            QrModel::Micro => Version::Micro(4)
+        };
+        QrCode::with_version(content, version, ec_level).is_ok()
+    }
+}
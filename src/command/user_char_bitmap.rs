@@ -0,0 +1,59 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+use alloc::vec::Vec;
+
+/// Bitmap of a user-defined character
+///
+/// The bitmap is stored by columns, from left to right. Each column takes `height` bytes, from top to bottom, with the most significant bit of each byte on top. The size of the character cell depends on the font:
+///
+/// * Font A: 12 dots wide and 24 dots high, so 3 bytes per column.
+/// * Font B: 9 dots wide and 17 dots high, so 3 bytes per column too, with the 7 lowest bits of the last byte unused.
+///
+/// See [DefineUserChar](super::Command::DefineUserChar).
+///
+/// ```rust
+/// use escpos_rs::command::UserCharBitmap;
+/// // A full block for font A
+/// let bitmap = UserCharBitmap::new(3, vec![0xff; 36]);
+/// assert_eq!(12, bitmap.width());
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UserCharBitmap {
+    /// Bytes per column
+    height: u8,
+    /// Column data, `height` bytes per column
+    data: Vec<u8>
+}
+
+impl UserCharBitmap {
+    /// Creates a bitmap from its columns
+    ///
+    /// If the last column is incomplete, it gets filled with blank dots.
+    pub fn new(height: u8, mut data: Vec<u8>) -> UserCharBitmap {
+        let height = height.max(1);
+        let remainder = data.len() % (height as usize);
+        if remainder != 0 {
+            data.resize(data.len() + (height as usize) - remainder, 0x00);
+        }
+        UserCharBitmap {
+            height,
+            data
+        }
+    }
+
+    /// Bytes per column
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// Number of columns, in dots
+    pub fn width(&self) -> u8 {
+        (self.data.len() / (self.height as usize)) as u8
+    }
+
+    /// Column data
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
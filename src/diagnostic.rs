@@ -0,0 +1,59 @@
+#[cfg(feature = "log")]
+extern crate log;
+
+use crate::ImageMode;
+
+/// Events worth reporting, that do not stop the printing
+///
+/// By default, these events get logged through the [log](https://docs.rs/log) crate (if the `log` feature is active). A custom handler can be set with the [Printer](crate::Printer)'s [set_diagnostics_handler](crate::Printer::set_diagnostics_handler) method.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnostic {
+    /// An image had to be scaled while printing, as it was not cached for the printer's width
+    ImageBuiltOnTheFly {
+        /// Width the image was scaled to
+        width: u16
+    },
+    /// The printer does not support the mode of an image, so another one was used
+    ImageModeFallback {
+        /// Mode of the image
+        requested: ImageMode,
+        /// Mode the image got printed with
+        used: ImageMode
+    },
+    /// It was not possible to find out if the kernel driver is active for the usb device
+    KernelDriverUnknown,
+    /// The command to turn off a style could not be sent when its scope ended
+    ScopeEndFailed {
+        /// Description of the error
+        reason: String
+    },
+    /// A matching device could not be opened, so it was left out of the printers found
+    DeviceSkipped {
+        /// Path or usb location of the device
+        device: String,
+        /// Description of the error
+        reason: String
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Diagnostic::ImageBuiltOnTheFly{width} => write!(formatter, "Building an image on the fly in non-mutable mode for width {}. Consider caching the width.", width),
+            Diagnostic::ImageModeFallback{requested, used} => write!(formatter, "The printer does not support the image mode {:?}, using {:?} instead.", requested, used),
+            Diagnostic::KernelDriverUnknown => write!(formatter, "Could not find out if kernel driver is active, might encounter a problem soon."),
+            Diagnostic::ScopeEndFailed{reason} => write!(formatter, "Could not turn off the style at the end of the scope, {}", reason),
+            Diagnostic::DeviceSkipped{device, reason} => write!(formatter, "Skipping the printer at {}, could not open it: {}", device, reason)
+        }
+    }
+}
+
+/// Sends the diagnostic to the handler, or to the log if there is none
+pub(crate) fn report(handler: Option<&dyn Fn(Diagnostic)>, diagnostic: Diagnostic) {
+    if let Some(handler) = handler {
+        handler(diagnostic);
+    } else {
+        #[cfg(feature = "log")]
+        log::warn!("{}", diagnostic);
+    }
+}
@@ -1,12 +1,22 @@
+use alloc::{format, string::{String, ToString}};
+
 /// Errors that this crate throws.
 #[derive(Debug)]
 pub enum Error {
-    /// Error related to rusb
+    /// Error related to rusb, only with the `std` feature
+    #[cfg(feature = "std")]
     RusbError(rusb::Error),
     /// For text printing, the replaced sequence could not be found
     CP437Error(String),
-    /// Error regarding image treatment
+    /// Error while writing to a file connection, only with the `std` feature
+    #[cfg(feature = "std")]
+    IoError(std::io::Error),
+    /// Error regarding image treatment, only with the `std` feature
+    #[cfg(feature = "std")]
     ImageError(image::ImageError),
+    /// A QR code could not be generated for the content, only with the `std` feature
+    #[cfg(feature = "std")]
+    QrError(qrcode::types::QrError),
     /// This means no bulk endpoint could be found
     NoBulkEndpoint,
     /// No replacement string for an instruction was found
@@ -26,15 +36,57 @@ pub enum Error {
     NoWidth,
     NoQrContent(String),
     NoQrContents,
+    /// The flag required by a conditional instruction was not found
+    NoFlagFound(String),
+    /// The image required by an image reference was not found
+    NoImageFound(String),
+    /// The style was not found in the printer's style sheet
+    NoStyleFound(String),
+    /// The printer profile does not declare a black mark sensor
+    NoBlackMarkSensor,
+    /// The printer profile does not declare a paper presenter
+    NoPresenter,
+    /// The printer profile does not declare two-color printing
+    NoColorSupport,
+    /// The printer profile does not declare support for real-time commands
+    NoRealTimeCommands,
+    /// The instruction can not be laid out as plain text, like images or cuts
+    UnsupportedForLayout,
+    /// User-defined characters only go from 32 to 126
+    InvalidUserCharCode(u8),
+    /// The printer did not get ready in time
+    Timeout,
+    /// The name of a charset, code table or font is not known
+    UnknownName(String),
+    /// The printer profile does not support any bit image mode
+    NoImageModeFound,
+    /// The image has no dots left once scaled to the given width
+    EmptyImage{width: u16},
+    /// The box to fit an image in has no width or no height
+    EmptyBox{width: u16, height: u16},
+    /// The kinds of real-time status only go from 1 to 4
+    InvalidStatusRequest(u8),
+    /// The json value does not follow the print data structure
+    InvalidPrintData(String),
+    /// Error while reading json content, only returned with the `json` feature
+    JsonError(String),
+    /// Error while writing or reading the binary format, only returned with the `binary` feature
+    BinaryError(String),
     Encoding
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl core::fmt::Display for Error {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         let content = match self {
+            #[cfg(feature = "std")]
             Error::RusbError(e) => format!("rusb error: {}", e),
             Error::CP437Error(detail) => format!("CP437 error: {}", detail),
+            #[cfg(feature = "std")]
+            Error::IoError(e) => format!("io error: {}", e),
+            #[cfg(feature = "std")]
             Error::ImageError(e) => format!("Image error: {}", e),
+            #[cfg(feature = "std")]
+            Error::QrError(e) => format!("QR error: {}", e),
             Error::NoBulkEndpoint => "No bulk endpoint could be found".to_string(),
             Error::NoReplacementFound(replacement) => format!("Could not find replacement for tag {{{}}}", replacement),
             Error::NoPrintData => "Print data must be supplied for this instruction".to_string(),
@@ -48,10 +100,29 @@ impl std::fmt::Display for Error {
             Error::NoWidth => "No width was found for the selected font".to_string(),
             Error::NoQrContent(name) => format!("Could not find qr code content for \"{}\"", name),
             Error::NoQrContents => "Could not find qr contents".to_string(),
+            Error::NoFlagFound(flag) => format!("Could not find flag \"{}\"", flag),
+            Error::NoImageFound(image) => format!("Could not find image \"{}\"", image),
+            Error::NoStyleFound(style) => format!("Could not find style \"{}\"", style),
+            Error::NoBlackMarkSensor => "The printer profile has no black mark sensor".to_string(),
+            Error::NoPresenter => "The printer profile has no paper presenter".to_string(),
+            Error::NoColorSupport => "The printer profile does not support two-color printing".to_string(),
+            Error::NoRealTimeCommands => "The printer profile does not support real-time commands".to_string(),
+            Error::UnsupportedForLayout => "Only text instructions can be laid out side by side".to_string(),
+            Error::InvalidUserCharCode(code) => format!("User-defined characters go from 32 to 126, got {}", code),
+            Error::Timeout => "The printer did not get ready in time".to_string(),
+            Error::InvalidStatusRequest(status) => format!("The kind of status goes from 1 to 4, got {}", status),
+            Error::NoImageModeFound => "The printer profile does not support any image mode".to_string(),
+            Error::EmptyImage{width} => format!("The image has no dots left when scaled to {} dots", width),
+            Error::EmptyBox{width, height} => format!("The box to fit the image needs a width and a height, got {}x{}", width, height),
+            Error::UnknownName(name) => format!("Unknown name \"{}\"", name),
+            Error::InvalidPrintData(detail) => format!("Invalid print data, {}", detail),
+            Error::JsonError(detail) => format!("json error: {}", detail),
+            Error::BinaryError(detail) => format!("binary format error: {}", detail),
             Error::Encoding => "An unsupported utf-8 character was found when passing to cp437".to_string()
         };
         write!(formatter, "{}", content)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error{}
\ No newline at end of file
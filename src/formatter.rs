@@ -1,10 +1,159 @@
+use alloc::{format, vec, vec::Vec, string::{String, ToString}};
+use crate::{
+    Justification,
+    command::CodeTable
+};
+#[cfg(feature = "std")]
+use crate::{Error, command::Command};
+
+pub use self::table_spec::{TableSpec, ColumnSpec, ColumnWidth};
+
+mod table_spec;
+
 /// Options to print tables
+///
+/// The defaults use `-` for the header division line, and trim the spaces around each cell. Options other than the patterns get changed through their setters, so more of them can be added later on.
+///
+/// ```rust
+/// # use escpos_rs::TableOptions;
+/// let mut table_options = TableOptions::default();
+/// assert_eq!(Some("-".to_string()), table_options.header_division_pattern);
+/// table_options.set_fill(Some('='));
+/// assert_eq!(Some('='), table_options.fill());
+/// ```
 #[derive(Clone, Debug)]
 pub struct TableOptions {
     /// Indicates the header/row division character
     pub header_division_pattern: Option<String>,
     /// Inicates if a pattern should be used to bridge between columns
-    pub join_columns_pattern: Option<String>
+    pub join_columns_pattern: Option<String>,
+    /// Character placed at the left end of the header division line
+    left_corner: Option<char>,
+    /// Character placed at the right end of the header division line
+    right_corner: Option<char>,
+    /// Character that fills the header division line between the corners, overriding the header division pattern
+    fill: Option<char>,
+    /// If spaces around each cell get removed before placing it in its column
+    trim_cells: bool,
+    /// Code table of each column, for the tables printed by the printer
+    column_code_tables: Vec<Option<CodeTable>>
+}
+
+impl Default for TableOptions {
+    fn default() -> TableOptions {
+        TableOptions {
+            header_division_pattern: Some("-".into()),
+            join_columns_pattern: None,
+            left_corner: None,
+            right_corner: None,
+            fill: None,
+            trim_cells: true,
+            column_code_tables: Vec::new()
+        }
+    }
+}
+
+impl TableOptions {
+    /// Sets the character placed at the left end of the header division line
+    pub fn set_left_corner(&mut self, left_corner: Option<char>) {
+        self.left_corner = left_corner;
+    }
+
+    /// Character placed at the left end of the header division line
+    pub fn left_corner(&self) -> Option<char> {
+        self.left_corner
+    }
+
+    /// Sets the character placed at the right end of the header division line
+    pub fn set_right_corner(&mut self, right_corner: Option<char>) {
+        self.right_corner = right_corner;
+    }
+
+    /// Character placed at the right end of the header division line
+    pub fn right_corner(&self) -> Option<char> {
+        self.right_corner
+    }
+
+    /// Sets the character that fills the header division line between the corners, overriding the header division pattern
+    pub fn set_fill(&mut self, fill: Option<char>) {
+        self.fill = fill;
+    }
+
+    /// Character that fills the header division line between the corners
+    pub fn fill(&self) -> Option<char> {
+        self.fill
+    }
+
+    /// Sets if spaces around each cell get removed before placing it in its column
+    ///
+    /// Enabled by default, so values with stray spaces from user data do not throw off the alignment or get truncated early.
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let mut formatter = Formatter::new(20);
+    /// assert_eq!(
+    ///     formatter.duo_table(("Product", "Price"), vec![("Milk", "5.00")]),
+    ///     formatter.duo_table(("Product", "Price"), vec![("Milk", "  5.00  ")])
+    /// );
+    ///
+    /// formatter.modify_table_options(|table_options| table_options.set_trim_cells(false));
+    /// assert!(formatter.duo_table(("Product", "Price"), vec![("Milk", "  5.00  ")]).ends_with("Milk          5.00\n"));
+    /// ```
+    pub fn set_trim_cells(&mut self, trim_cells: bool) {
+        self.trim_cells = trim_cells;
+    }
+
+    /// If spaces around each cell get removed before placing it in its column
+    pub fn trim_cells(&self) -> bool {
+        self.trim_cells
+    }
+
+    /// Sets the code table of each column, for cells in a different language than the rest of the print
+    ///
+    /// The [Printer](crate::Printer)'s table methods ([duo_table](crate::Printer::duo_table), [trio_table](crate::Printer::trio_table) and [print_table](crate::Printer::print_table)) select the code table of a column right before each of its cells, and select the printer's current one again right after it. Columns without a code table (`None`, or missing from the list) keep the printer's current one. The formatter gives back text, so its own table methods ignore this option.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "std")] {
+    /// use escpos_rs::{Printer, PrinterProfile, TableOptions, command::{Command, CodeTable, Font}};
+    /// let path = std::env::temp_dir().join("escpos-rs-column-code-tables-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 12).build())?.unwrap();
+    /// let mut table_options = TableOptions::default();
+    /// table_options.set_column_code_tables(vec![None, Some(CodeTable::PC850)]);
+    /// printer.set_table_options(table_options);
+    /// printer.duo_table(("Item", "Name"), vec![("1", "Ø")])?;
+    ///
+    /// let pc850 = Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes();
+    /// let usa = Command::SelectCodeTable{code_table: CodeTable::USA}.as_bytes();
+    /// let mut target = b"Item".to_vec();
+    /// target.extend_from_slice(&pc850);
+    /// target.extend_from_slice(b"    Name");
+    /// target.extend_from_slice(&usa);
+    /// target.extend_from_slice(b"\n------------\n1");
+    /// target.extend_from_slice(&pc850);
+    /// // Ø is 0x9d in PC850, right aligned in its column
+    /// target.extend_from_slice(b"          \x9d");
+    /// target.extend_from_slice(&usa);
+    /// target.push(b'\n');
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # }
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_column_code_tables(&mut self, column_code_tables: Vec<Option<CodeTable>>) {
+        self.column_code_tables = column_code_tables;
+    }
+
+    /// Code table of each column, for the tables printed by the printer
+    pub fn column_code_tables(&self) -> &[Option<CodeTable>] {
+        &self.column_code_tables
+    }
+}
+
+/// Cells of a table, already padded to their columns, before joining them in lines
+pub(crate) struct TableCells {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>
 }
 
 /// Helper structure to format text
@@ -14,21 +163,54 @@ pub struct Formatter {
     /// Inner table options
     table_options: TableOptions,
     /// Width to use for formatting
-    width: u8
+    width: u8,
+    /// If spaces at the end of each line get removed
+    trim_trailing_spaces: bool
 }
 
 impl Formatter {
     /// Creates a new formatter with a default width
     pub fn new(width: u8) -> Formatter {
         Formatter{
-            table_options: TableOptions {
-                header_division_pattern: Some("-".into()),
-                join_columns_pattern: None
-            },
-            width
+            table_options: TableOptions::default(),
+            width,
+            trim_trailing_spaces: true
         }
     }
 
+    /// Changes the width used for formatting
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let mut formatter = Formatter::new(20);
+    /// formatter.set_width(32);
+    /// assert_eq!(32, formatter.width());
+    /// ```
+    pub fn set_width(&mut self, width: u8) {
+        self.width = width;
+    }
+
+    /// Gives back the width used for formatting
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// Enables or disables the removal of spaces at the end of each line
+    ///
+    /// Padding at the end of a line is invisible on paper, so by default it gets removed from tables and section headers to send less bytes to the printer. Leading padding (like the one of right-aligned columns) is always kept. The [ruler](Formatter::ruler) always spans the whole width.
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let mut formatter = Formatter::new(8);
+    /// assert_eq!(" Subtot\n", formatter.section("Subtotal", '-'));
+    ///
+    /// formatter.set_trim_trailing_spaces(false);
+    /// assert_eq!(" Subtot \n", formatter.section("Subtotal", '-'));
+    /// ```
+    pub fn set_trim_trailing_spaces(&mut self, state: bool) {
+        self.trim_trailing_spaces = state;
+    }
+
     /// Sets a new set of table options
     ///
     /// To modify just one parameter in a simpler way, check the [modify_table_options](self::Formatter::modify_table_options) method.
@@ -36,10 +218,30 @@ impl Formatter {
     /// ```rust
     /// # use escpos_rs::{Formatter, TableOptions};
     /// let mut formatter = Formatter::new(20);
-    /// formatter.set_table_options(TableOptions {
-    ///     header_division_pattern: Some(".-".into()),
-    ///     join_columns_pattern: Some(".".into())
-    /// });
+    /// let mut table_options = TableOptions::default();
+    /// table_options.header_division_pattern = Some(".-".into());
+    /// table_options.join_columns_pattern = Some(".".into());
+    /// formatter.set_table_options(table_options);
+    /// ```
+    ///
+    /// Corners and a fill character allow boxed dividers
+    ///
+    /// ```rust
+    /// # use escpos_rs::{Formatter, TableOptions};
+    /// let mut formatter = Formatter::new(12);
+    /// let mut table_options = TableOptions::default();
+    /// table_options.set_left_corner(Some('+'));
+    /// table_options.set_right_corner(Some('+'));
+    /// table_options.set_fill(Some('='));
+    /// formatter.set_table_options(table_options);
+    ///
+    /// let target = r#"
+    /// Product  Qty
+    /// +==========+
+    /// Milk       3
+    /// "#.trim_start();
+    ///
+    /// assert_eq!(target, formatter.duo_table(("Product", "Qty"), vec![("Milk", "3")]));
     /// ```
     pub fn set_table_options(&mut self, table_options: TableOptions) {
         self.table_options = table_options
@@ -72,6 +274,58 @@ impl Formatter {
         modifier(&mut self.table_options);
     }
 
+    /// Creates a numbered ruler, spanning the whole width
+    ///
+    /// The first line marks the tens, and the second one the units. Useful to find out how many columns fit in a line of the printer.
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let formatter = Formatter::new(32);
+    /// let target = r#"
+    ///          1         2         3  
+    /// 12345678901234567890123456789012
+    /// "#.trim_start_matches('\n');
+    /// assert_eq!(target, formatter.ruler());
+    /// assert!(formatter.ruler().lines().all(|line| line.len() == 32));
+    /// ```
+    pub fn ruler(&self) -> String {
+        let tens: String = (1..=self.width as usize).map(|column| {
+            if column % 10 == 0 {
+                core::char::from_digit(((column / 10) % 10) as u32, 10).unwrap_or(' ')
+            } else {
+                ' '
+            }
+        }).collect();
+        let units: String = (1..=self.width as usize).map(|column| {
+            core::char::from_digit((column % 10) as u32, 10).unwrap_or(' ')
+        }).collect();
+        format!("{}\n{}\n", tens, units)
+    }
+
+    /// Creates a section header, with the label centered between fill characters
+    ///
+    /// The label is surrounded by a space on each side. If it does not fit, it gets truncated. When the fill can't be split evenly, the extra character goes to the right.
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let formatter = Formatter::new(20);
+    /// assert_eq!("------ ITEMS -------\n", formatter.section("ITEMS", '-'));
+    /// assert_eq!("====== TOTAL =======\n", formatter.section("TOTAL", '='));
+    /// assert_eq!(" A much longer labe\n", formatter.section("A much longer label", '-'));
+    /// ```
+    pub fn section<A: AsRef<str>>(&self, label: A, fill: char) -> String {
+        let width = self.width as usize;
+        let label: String = label.as_ref().chars().take(width.saturating_sub(2)).collect();
+        let remaining = width.saturating_sub(label.chars().count() + 2);
+        let left = remaining / 2;
+        let right = remaining - left;
+        self.trim(format!("{} {} {}\n",
+            fill.to_string().repeat(left),
+            label,
+            fill.to_string().repeat(right)
+        ))
+    }
+
     /// Splits a string by whitespaces, according to the given width
     ///
     /// Notice that the final line will not contain a new line at the end.
@@ -82,18 +336,22 @@ impl Formatter {
     /// let formatter = Formatter::new(16);
     /// let res = formatter.space_split("Sentence with two lines.");
     /// assert_eq!("Sentence with\ntwo lines.", res.as_str());
+    ///
+    /// // Tabs are not treated as whitespace, so they reach the printer
+    /// assert_eq!("Milk\t5.00", formatter.space_split("Milk\t5.00").as_str());
     /// ```
     pub fn space_split<A: AsRef<str>>(&self, source: A) -> String {
         let mut result = source.as_ref().split("\n").map(|line| {
             // Now, for each line, we split it into words.
             let mut current_line = String::new();
             let mut broken_lines = Vec::new();
-            for word in line.split_whitespace() {
+            // Tabs are kept, so they can move to the printer's tab stops
+            for word in line.split(|c: char| c.is_whitespace() && c != '\t').filter(|word| !word.is_empty()) {
                 let num_chars = word.chars().count();
                 // The one being added marks the space
                 if current_line.len() + num_chars + 1 < self.width.into() {
                     // Easy to add to the current line, the conditional if is for the first word of them all.
-                    current_line += &format!("{}{}", if current_line.len() == 0 {""} else {" "}, word);
+                    current_line += &format!("{}{}", if current_line.is_empty() {""} else {" "}, word);
                 } else {
                     // We have to terminate the current line, in case it contains something
                     if !current_line.is_empty() {
@@ -106,10 +364,10 @@ impl Formatter {
                         // We use a char iterator to split this into lines
                         let mut chars = word.chars();
                         let mut word_fragment: String = chars.by_ref().take(self.width.into()).collect();
-                        broken_lines.push(format!("{}",word_fragment));
+                        broken_lines.push(word_fragment.clone());
                         while !word_fragment.is_empty() {
                             word_fragment = chars.by_ref().take(self.width.into()).collect();
-                            broken_lines.push(format!("{}",word_fragment));
+                            broken_lines.push(word_fragment.clone());
                         }
                     }
                 }
@@ -128,6 +386,68 @@ impl Formatter {
         result
     }
 
+    /// Splits a string by whitespaces, keeping the indentation of each line
+    ///
+    /// Works like [space_split](Formatter::space_split), but the leading spaces of each line get applied to every line produced when wrapping it, which keeps nested lists readable. Indentations that leave no room for the text get ignored.
+    ///
+    /// ```rust
+    /// use escpos_rs::Formatter;
+    ///
+    /// let formatter = Formatter::new(16);
+    /// let res = formatter.wrap_preserving_indent("Ingredients:\n    tomato, basil, mozzarella");
+    /// assert_eq!("Ingredients:\n    tomato,\n    basil,\n    mozzarella", res.as_str());
+    ///
+    /// let res = formatter.wrap_preserving_indent("    Extra ham please\n");
+    /// assert_eq!("    Extra ham\n    please\n", res.as_str());
+    /// ```
+    pub fn wrap_preserving_indent<A: AsRef<str>>(&self, source: A) -> String {
+        source.as_ref().split('\n').map(|line| {
+            let body = line.trim_start_matches(' ');
+            let mut indent = line.len() - body.len();
+            // At least two columns are needed for space splitting to make progress
+            if indent + 2 > self.width as usize {
+                indent = 0;
+            }
+            let inner = Formatter::new(self.width - indent as u8);
+            inner.space_split(body).split('\n')
+                .map(|wrapped| if wrapped.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", " ".repeat(indent), wrapped)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Splits a string by whitespaces, and aligns each line within the width
+    ///
+    /// Every line, including the last one, ends with a new line.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Formatter, Justification};
+    ///
+    /// let formatter = Formatter::new(12);
+    /// assert_eq!("Total\n", formatter.justify("Total", Justification::Left));
+    /// assert_eq!("   Total\n", formatter.justify("Total", Justification::Center));
+    /// assert_eq!("       Total\n", formatter.justify("Total", Justification::Right));
+    ///
+    /// assert_eq!("Thanks for\nyour visit\n", formatter.justify("Thanks for your visit", Justification::Left));
+    /// assert_eq!(" Thanks for\n your visit\n", formatter.justify("Thanks for your visit", Justification::Center));
+    /// assert_eq!("  Thanks for\n  your visit\n", formatter.justify("Thanks for your visit", Justification::Right));
+    /// ```
+    pub fn justify<A: AsRef<str>>(&self, source: A, justification: Justification) -> String {
+        let width = self.width as usize;
+        let content: String = self.space_split(source.as_ref().trim_end_matches('\n')).split('\n').map(|line| {
+            match justification {
+                Justification::Left => format!("{}\n", line),
+                Justification::Center => format!("{:^1$}\n", line, width),
+                Justification::Right => format!("{:>1$}\n", line, width)
+            }
+        }).collect();
+        self.trim(content)
+    }
+
     /// Creates a table with two columns
     ///
     /// In case the headers do not fit with at least one space between, priority will be given to the second header, and the last remaining character from the first header will be replaced by a dot. If the second header would need to be shortened to less than 3 characters, then the first header will now also be truncated, with the same dot replacing the last charcater from the remaining part of the first header.
@@ -152,55 +472,28 @@ impl Formatter {
     /// assert_eq!(target, formatter.duo_table(header, rows));
     /// ```
     pub fn duo_table<A: Into<String>, B: Into<String>, C: IntoIterator<Item = (D, E)>, D: Into<String>, E: Into<String>>(&self, header: (A, B), rows: C) -> String {
-        // Aux closure to create each row.
-        let aux_duo_table = |mut first: String, mut second: String, width: u8, replace_last: Option<char>| -> String {
-            let row_width = first.len() + second.len();
-            let (column_1, column_2) = if row_width < width as usize {
-                (first, second)
-            } else {
-                // If the second column requires all the space, we give it
-                if second.len() + 4 > (width as usize) {
-                    if let Some(replacement) = replace_last {
-                        second.truncate((width as usize) - 5);
-                        second += &replacement.to_string();
-                    } else {
-                        second.truncate((width as usize) - 4);
-                    }
-                }
-
-                // We calculate the remaining space for the second word now.
-                let remaining = (width as usize) - second.len();
-                // We just need to shorten the second word. We need to include the separating whitespace
-                if first.len() > remaining {
-                    if let Some(replacement) = replace_last {
-                        first.truncate(remaining - 2);
-                        first += &replacement.to_string();
-                    } else {
-                        first.truncate(remaining - 1);
-                    }
-                }
-
-                (first, second)
-            };
-
-            format!("{} {:>2$}\n",
-                column_1,
-                column_2,
-                (width as usize) - (column_1.len() + 1)
-            )
-        };
-
-        let mut content = aux_duo_table(header.0.into(), header.1.into(), self.width, Some('.'));
+        self.render(self.duo_table_cells(header, rows))
+    }
 
-        if let Some(hdp) = self.print_header_division_pattern() {
-            content += &hdp;
+    /// Cells of a [duo_table](Formatter::duo_table)
+    pub(crate) fn duo_table_cells<A: Into<String>, B: Into<String>, C: IntoIterator<Item = (D, E)>, D: Into<String>, E: Into<String>>(&self, header: (A, B), rows: C) -> TableCells {
+        TableCells {
+            header: duo_cells(self.cell(header.0), self.cell(header.1), self.width, Some('.')),
+            rows: rows.into_iter().map(|row| duo_cells(self.cell(row.0), self.cell(row.1), self.width, None)).collect()
         }
+    }
 
-        for row in rows {
-            let (first, second) = (row.0.into(), row.1.into());
-            content += &aux_duo_table(first, second, self.width, None);
-        }
-        content
+    /// Creates a line with a label to the left and an amount to the right
+    ///
+    /// Works like a single row of a [duo_table](Formatter::duo_table), without the header. Handy for the items and totals of a receipt.
+    ///
+    /// ```rust
+    /// # use escpos_rs::Formatter;
+    /// let formatter = Formatter::new(20);
+    /// assert_eq!("Milk            5.00\n", formatter.line_item("Milk", "5.00"));
+    /// ```
+    pub fn line_item<A: Into<String>, B: Into<String>>(&self, label: A, amount: B) -> String {
+        self.trim(duo_row(label.into(), amount.into(), self.width, None))
     }
 
     /// Creates a table with three columns
@@ -227,8 +520,13 @@ impl Formatter {
     /// assert_eq!(target, formatter.trio_table(header, rows));
     /// ```
     pub fn trio_table<A: Into<String>, B: Into<String>, C: Into<String>, D: IntoIterator<Item = (E, F, G)>, E: Into<String>, F: Into<String>, G: Into<String>>(&self, header: (A, B, C), rows: D) -> String {
+        self.render(self.trio_table_cells(header, rows))
+    }
+
+    /// Cells of a [trio_table](Formatter::trio_table)
+    pub(crate) fn trio_table_cells<A: Into<String>, B: Into<String>, C: Into<String>, D: IntoIterator<Item = (E, F, G)>, E: Into<String>, F: Into<String>, G: Into<String>>(&self, header: (A, B, C), rows: D) -> TableCells {
         // Auxiliary closure for printing
-        let aux_trio_table = |mut first: String, mut second: String, mut third: String, width: u8, limits: (u8, u8), replace_last: Option<char>| -> String {
+        let aux_trio_table = |mut first: String, mut second: String, mut third: String, width: u8, limits: (u8, u8), replace_last: Option<char>| -> Vec<String> {
             if first.len() > limits.0 as usize {
                 let max_width = (limits.0 as usize) - 1;
                 if let Some(replacement) = replace_last {
@@ -256,24 +554,21 @@ impl Formatter {
                     third.truncate(max_width);
                 }
             }
-            format!("{:<3$} {:^4$} {:>5$}\n",
-                first,
-                second,
-                third,
-                (limits.0 - 1) as usize,
-                (limits.1 - limits.0) as usize,
-                (width - limits.1 - 1) as usize
-            )
+            vec![
+                format!("{:<1$}", first, (limits.0 - 1) as usize),
+                format!("{:^1$}", second, (limits.1 - limits.0) as usize),
+                format!("{:>1$}", third, (width - limits.1 - 1) as usize)
+            ]
         };
 
         // First step, is to find the maximum desirable width of a column.
-        let header: (String, String, String) = (header.0.into(), header.1.into(), header.2.into());
+        let header: (String, String, String) = (self.cell(header.0), self.cell(header.1), self.cell(header.2));
         let mut max_left = header.0.len();
         let mut max_middle = header.1.len();
         let mut max_right = header.2.len();
 
         // I was not able to do 2 for loops with the IntoIterator trait with borrowed items :(
-        let rows: Vec<(String, String, String)> = rows.into_iter().map(|(a, b, c)| (a.into(), b.into(), c.into())).collect();
+        let rows: Vec<(String, String, String)> = rows.into_iter().map(|(a, b, c)| (self.cell(a), self.cell(b), self.cell(c))).collect();
         
         // Now we compare to all rows
         for row in &rows {
@@ -292,7 +587,7 @@ impl Formatter {
             // Nothing to do, easy peasy
             ((max_left + 1) as u8, (self.width as usize - max_right - 1) as u8)
         } else {
-            let mut limits = (0u8, self.width as u8);
+            let mut limits = (0u8, self.width);
             // The left-most column must be at least 4 characters wide, with the lowest priority
             if max_middle + max_right + 4 > (self.width as usize) {
                 limits.0 = 4;
@@ -311,25 +606,203 @@ impl Formatter {
             limits
         };
 
-        let mut content = aux_trio_table(header.0, header.1, header.2, self.width, limits, None);
+        TableCells {
+            header: aux_trio_table(header.0, header.1, header.2, self.width, limits, None),
+            rows: rows.into_iter().map(|row| aux_trio_table(row.0, row.1, row.2, self.width, limits, None)).collect()
+        }
+    }
+
+    /// Creates a table following a column spec
+    ///
+    /// Unlike [duo_table](Formatter::duo_table) and [trio_table](Formatter::trio_table), the number of columns and their widths come from the [TableSpec](crate::TableSpec). Cells that do not fit in their column get truncated, and missing cells are left empty.
+    ///
+    /// ```rust
+    /// # use escpos_rs::{Formatter, TableSpec, Justification};
+    /// let formatter = Formatter::new(32);
+    /// let spec = TableSpec::new()
+    ///     .with_fixed_column("Qty", Justification::Right, 3)
+    ///     .with_weighted_column("Product", Justification::Left, 2)
+    ///     .with_weighted_column("Price", Justification::Right, 1);
+    /// let rows = vec![
+    ///     vec!["2", "Milk", "10.00"],
+    ///     vec!["12", "Chocolate chip cookies", "3.50"]
+    /// ];
+    ///
+    /// // We use trim_start just to show the table nicer in this example.
+    /// let target = r#"
+    /// Qty Product                Price
+    /// --------------------------------
+    ///   2 Milk                   10.00
+    ///  12 Chocolate chip coo      3.50
+    /// "#.trim_start();
+    ///
+    /// assert_eq!(target, formatter.table(&spec, rows));
+    /// ```
+    pub fn table<A: IntoIterator<Item = B>, B: IntoIterator<Item = C>, C: Into<String>>(&self, spec: &TableSpec, rows: A) -> String {
+        self.render(self.table_cells(spec, rows))
+    }
+
+    /// Cells of a [table](Formatter::table)
+    pub(crate) fn table_cells<A: IntoIterator<Item = B>, B: IntoIterator<Item = C>, C: Into<String>>(&self, spec: &TableSpec, rows: A) -> TableCells {
+        let widths = spec.widths(self.width);
+
+        // Auxiliary closure to create each row
+        let aux_table = |cells: Vec<String>| -> Vec<String> {
+            let mut cells = cells.into_iter();
+            spec.columns().iter().zip(&widths).map(|(column, width)| {
+                let cell: String = cells.next().unwrap_or_default().chars().take(*width).collect();
+                match column.justification {
+                    Justification::Left => format!("{:<1$}", cell, width),
+                    Justification::Center => format!("{:^1$}", cell, width),
+                    Justification::Right => format!("{:>1$}", cell, width)
+                }
+            }).collect()
+        };
+
+        TableCells {
+            header: aux_table(spec.columns().iter().map(|column| self.cell(column.name.clone())).collect()),
+            rows: rows.into_iter().map(|row| aux_table(row.into_iter().map(|cell| self.cell(cell)).collect())).collect()
+        }
+    }
+
+    /// Joins the cells of a table in lines, with the header division line after the header
+    pub(crate) fn render(&self, cells: TableCells) -> String {
+        let mut content = format!("{}\n", cells.header.join(" "));
 
         if let Some(hdp) = self.print_header_division_pattern() {
             content += &hdp;
         }
 
-        for row in rows {
-            content += &aux_trio_table(row.0, row.1, row.2, self.width, limits, None);
+        for row in cells.rows {
+            content += &format!("{}\n", row.join(" "));
+        }
+        self.trim(content)
+    }
+
+    /// Encodes the cells of a table for the printer, with the code table of each column around its cells
+    #[cfg(feature = "std")]
+    pub(crate) fn encode_table(&self, cells: TableCells, code_table: &CodeTable) -> Result<Vec<u8>, Error> {
+        let mut target = self.encode_table_line(cells.header, code_table)?;
+
+        if let Some(hdp) = self.print_header_division_pattern() {
+            target.append(&mut code_table.encode(hdp)?);
+        }
+
+        for row in cells.rows {
+            target.append(&mut self.encode_table_line(row, code_table)?);
+        }
+        Ok(target)
+    }
+
+    // Encodes a line of cells, keeping the separator in front of each cell
+    #[cfg(feature = "std")]
+    fn encode_table_line(&self, cells: Vec<String>, code_table: &CodeTable) -> Result<Vec<u8>, Error> {
+        let mut cells: Vec<String> = cells.into_iter().enumerate().map(|(idx, cell)| if idx > 0 { format!(" {}", cell) } else { cell }).collect();
+        if self.trim_trailing_spaces {
+            // Same as trimming the joined line, cell by cell from the end
+            for cell in cells.iter_mut().rev() {
+                *cell = cell.trim_end_matches(' ').to_string();
+                if !cell.is_empty() {
+                    break;
+                }
+            }
+        }
+        encode_cells(&cells, &self.table_options.column_code_tables, code_table)
+    }
+
+    // Removes the spaces at the end of each line, if enabled
+    fn trim(&self, content: String) -> String {
+        if self.trim_trailing_spaces {
+            trim_trailing_spaces(&content)
+        } else {
+            content
+        }
+    }
+
+    // Content of a table cell, without the spaces around it if enabled
+    fn cell<A: Into<String>>(&self, content: A) -> String {
+        let content = content.into();
+        if self.table_options.trim_cells {
+            content.trim_matches(' ').to_string()
+        } else {
+            content
         }
-        content
     }
 
     fn print_header_division_pattern(&self) -> Option<String> {
-        if let Some(header_division_pattern) = &self.table_options.header_division_pattern {
-            let mut line = header_division_pattern.repeat((self.width as usize) / header_division_pattern.len() + 1);
-            line.truncate(self.width as usize);
-            Some(line + "\n")
+        // The fill character takes precedence over the pattern
+        let pattern = match (&self.table_options.fill, &self.table_options.header_division_pattern) {
+            (Some(fill), _) => fill.to_string(),
+            (None, Some(header_division_pattern)) if !header_division_pattern.is_empty() => header_division_pattern.clone(),
+            _ => return None
+        };
+        let left_corner = self.table_options.left_corner.map(String::from).unwrap_or_default();
+        let right_corner = self.table_options.right_corner.map(String::from).unwrap_or_default();
+        // Space left for the pattern, between both corners
+        let inner_width = (self.width as usize).saturating_sub(left_corner.chars().count() + right_corner.chars().count());
+        let line: String = pattern.chars().cycle().take(inner_width).collect();
+        Some(format!("{}{}{}\n", left_corner, line, right_corner))
+    }
+}
+
+/// Removes the spaces at the end of each line, keeping the line breaks
+fn trim_trailing_spaces(content: &str) -> String {
+    content.split('\n').map(|line| line.trim_end_matches(' ')).collect::<Vec<_>>().join("\n")
+}
+
+// Auxiliar function to encode the cells of a row, each one with its own code table
+#[cfg(feature = "std")]
+pub(crate) fn encode_cells(cells: &[String], code_tables: &[Option<CodeTable>], default_code_table: &CodeTable) -> Result<Vec<u8>, Error> {
+    let mut result = Vec::new();
+    for (idx, cell) in cells.iter().enumerate() {
+        if let Some(Some(code_table)) = code_tables.get(idx) {
+            result.append(&mut Command::SelectCodeTable{code_table: code_table.clone()}.as_bytes());
+            result.append(&mut code_table.encode(cell)?);
+            result.append(&mut Command::SelectCodeTable{code_table: default_code_table.clone()}.as_bytes());
         } else {
-            None
+            result.append(&mut default_code_table.encode(cell)?);
         }
     }
-}
\ No newline at end of file
+    result.push(b'\n');
+    Ok(result)
+}
+
+// Auxiliar function to create each row of a two column table
+fn duo_row(first: String, second: String, width: u8, replace_last: Option<char>) -> String {
+    format!("{}\n", duo_cells(first, second, width, replace_last).join(" "))
+}
+
+// Auxiliar function to create the cells of each row of a two column table
+fn duo_cells(mut first: String, mut second: String, width: u8, replace_last: Option<char>) -> Vec<String> {
+    let row_width = first.len() + second.len();
+    let (column_1, column_2) = if row_width < width as usize {
+        (first, second)
+    } else {
+        // If the second column requires all the space, we give it
+        if second.len() + 4 > (width as usize) {
+            if let Some(replacement) = replace_last {
+                second.truncate((width as usize) - 5);
+                second += &replacement.to_string();
+            } else {
+                second.truncate((width as usize) - 4);
+            }
+        }
+
+        // We calculate the remaining space for the second word now.
+        let remaining = (width as usize) - second.len();
+        // We just need to shorten the second word. We need to include the separating whitespace
+        if first.len() > remaining {
+            if let Some(replacement) = replace_last {
+                first.truncate(remaining - 2);
+                first += &replacement.to_string();
+            } else {
+                first.truncate(remaining - 1);
+            }
+        }
+
+        (first, second)
+    };
+
+    let padding = (width as usize) - (column_1.len() + 1);
+    vec![column_1, format!("{:>1$}", column_2, padding)]
+}
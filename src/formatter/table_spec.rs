@@ -0,0 +1,111 @@
+use crate::Justification;
+use alloc::{vec::Vec, string::String};
+
+/// Width of a column inside a [TableSpec](crate::TableSpec)
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnWidth {
+    /// The column always takes exactly this many characters
+    Fixed(u8),
+    /// The column takes a share of the space left by the fixed columns, proportional to its weight
+    Weight(u8)
+}
+
+/// Describes a single column of a [TableSpec](crate::TableSpec)
+#[derive(Clone, Debug)]
+pub struct ColumnSpec {
+    /// Name of the column, printed as its header
+    pub name: String,
+    /// Alignment of the content inside the column
+    pub justification: Justification,
+    /// Width of the column
+    pub width: ColumnWidth
+}
+
+/// Describes the columns of a table with an arbitrary number of columns
+///
+/// Each column has a name, an alignment and either a fixed width or a weight. Fixed columns get their width first, and whatever remains (after the single space separating each column) gets split between the weighted columns. See [Formatter::table](crate::Formatter::table).
+///
+/// ```rust
+/// # use escpos_rs::{TableSpec, Justification};
+/// let spec = TableSpec::new()
+///     .with_fixed_column("Qty", Justification::Right, 3)
+///     .with_weighted_column("Product", Justification::Left, 2)
+///     .with_weighted_column("Price", Justification::Right, 1);
+/// assert_eq!(3, spec.columns().len());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TableSpec {
+    columns: Vec<ColumnSpec>
+}
+
+impl TableSpec {
+    /// Creates a table spec without columns
+    pub fn new() -> TableSpec {
+        TableSpec::default()
+    }
+
+    /// Adds a column
+    pub fn with_column(mut self, column: ColumnSpec) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Adds a column with a fixed width
+    pub fn with_fixed_column<A: Into<String>>(self, name: A, justification: Justification, width: u8) -> Self {
+        self.with_column(ColumnSpec {
+            name: name.into(),
+            justification,
+            width: ColumnWidth::Fixed(width)
+        })
+    }
+
+    /// Adds a column that shares the remaining space according to its weight
+    pub fn with_weighted_column<A: Into<String>>(self, name: A, justification: Justification, weight: u8) -> Self {
+        self.with_column(ColumnSpec {
+            name: name.into(),
+            justification,
+            width: ColumnWidth::Weight(weight)
+        })
+    }
+
+    /// Gives back the columns of the spec
+    pub fn columns(&self) -> &[ColumnSpec] {
+        &self.columns
+    }
+
+    /// Calculates the width of each column for the total width
+    ///
+    /// The leftover characters from the weighted split go, one by one, to the first weighted columns.
+    pub(crate) fn widths(&self, total: u8) -> Vec<usize> {
+        let separators = self.columns.len().saturating_sub(1);
+        let fixed: usize = self.columns.iter().map(|column| match column.width {
+            ColumnWidth::Fixed(width) => width as usize,
+            ColumnWidth::Weight(_) => 0
+        }).sum();
+        let total_weight: usize = self.columns.iter().map(|column| match column.width {
+            ColumnWidth::Fixed(_) => 0,
+            ColumnWidth::Weight(weight) => weight as usize
+        }).sum();
+        let available = (total as usize).saturating_sub(separators + fixed);
+
+        let mut widths: Vec<usize> = self.columns.iter().map(|column| match column.width {
+            ColumnWidth::Fixed(width) => width as usize,
+            ColumnWidth::Weight(weight) if total_weight > 0 => available * (weight as usize) / total_weight,
+            ColumnWidth::Weight(_) => 0
+        }).collect();
+
+        let mut leftover = available - widths.iter().zip(&self.columns).filter(|(_, column)| matches!(column.width, ColumnWidth::Weight(weight) if weight > 0)).map(|(width, _)| width).sum::<usize>();
+        for (width, column) in widths.iter_mut().zip(&self.columns) {
+            if leftover == 0 {
+                break;
+            }
+            if let ColumnWidth::Weight(weight) = column.width {
+                if weight > 0 {
+                    *width += 1;
+                    leftover -= 1;
+                }
+            }
+        }
+        widths
+    }
+}
@@ -4,22 +4,37 @@ extern crate image;
 extern crate qrcode;
 
 pub use self::print_data::{PrintData, PrintDataBuilder};
-pub use self::justification::{Justification};
 pub use self::escpos_image::EscposImage;
+pub use self::dither_mode::{DitherMode, BayerSize};
+pub use self::alpha_mode::AlphaMode;
+pub use self::image_mode::ImageMode;
 
 mod print_data;
-mod justification;
 mod escpos_image;
+mod dither_mode;
+mod alpha_mode;
+mod image_mode;
+mod bitmap_font;
+#[cfg(feature = "preview")]
+mod preview;
 
 use qrcode::QrCode;
 use codepage_437::{IntoCp437, CP437_CONTROL};
 use crate::{
-    Error, PrinterProfile,
-    command::{Command, Font}
+    Error, Justification, PrinterProfile, Diagnostic,
+    command::{Command, CodeTable, ErrorCorrection, Font, QrModel},
+    formatter::encode_cells
 };
 use serde::{Serialize, Deserialize};
 use std::collections::HashSet;
 
+/// Approximate height of a text line, in dots, with the default line spacing
+const DOTS_PER_LINE: u32 = 30;
+/// Height, in dots, of each row of a printed image
+const BITMAP_ROW_HEIGHT: u32 = 24;
+/// Size, in dots, of each module of a natively printed qr code
+const NATIVE_QR_MODULE_SIZE: u8 = 6;
+
 /// Templates for recurrent prints
 ///
 /// The [Instruction](crate::Instruction) structure allows the creation of template prints, which could contain certain data that should change between prints (be it text, tables, or even qr codes).
@@ -51,7 +66,19 @@ pub enum Instruction {
         /// Justification of the content
         justification: Justification,
         /// Maps a string to be replaced, to a description of the string
-        replacements: Option<HashSet<String>>
+        replacements: Option<HashSet<String>>,
+        /// Maximum number of lines to print, the rest of the text gets dropped
+        #[serde(default)]
+        max_lines: Option<u8>
+    },
+    /// Text drawn with a bundled bitmap font, and printed as an image
+    TextImage {
+        /// Content to be printed
+        content: String,
+        /// Size, in dots, of each pixel of the font
+        scale: u8,
+        /// Justification of the content
+        justification: Justification
     },
     /// 2 column table
     DuoTable {
@@ -60,12 +87,18 @@ pub enum Instruction {
         /// Header to be displayed on the table
         header: (String, String),
         /// Font used for the table
-        font: Font
+        font: Font,
+        /// Code table to use for each column, if different from the printer's current one
+        #[serde(default)]
+        code_tables: Vec<Option<CodeTable>>
     },
     /// Table with three columns. Might be to tight for 50mm printers
     TrioTable {
         name: String,
-        header: (String, String, String)
+        header: (String, String, String),
+        /// Code table to use for each column, if different from the printer's current one
+        #[serde(default)]
+        code_tables: Vec<Option<CodeTable>>
     },
     /// Fancy table for really detailed prints
     QuadTable {
@@ -77,13 +110,45 @@ pub enum Instruction {
         /// Inner image
         image: EscposImage
     },
+    /// Prints an image given at printing time. This field is dynamic
+    ImageRef {
+        /// Name of the image, to be searched in the image list
+        name: String
+    },
     /// Prints a QR Code. This field is dynamic
     QRCode {
         /// Name of the QR code, to be searched in the qr code content list
         name: String
     },
+    /// Prints a QR code with a fixed content, generated by the printer if the profile supports native QR codes, and as an image otherwise
+    StaticQrCode {
+        /// Content of the QR code
+        content: String,
+        /// Justification of the QR code
+        justification: Justification
+    },
     /// Cuts the paper in place. Only for supported printers
-    Cut
+    Cut,
+    /// Prints one of two instructions, depending on a flag given at printing time
+    Conditional {
+        /// Name of the flag, to be searched in the flag list
+        flag: String,
+        /// Instruction to print if the flag is set
+        then: Box<Instruction>,
+        /// Instruction to print if the flag is not set
+        otherwise: Option<Box<Instruction>>
+    },
+    /// Prints an instruction with its own font, code table and justification, restoring the defaults afterwards
+    Group {
+        /// Font to select before the inner instruction
+        font: Option<Font>,
+        /// Code table to select before the inner instruction
+        code_table: Option<CodeTable>,
+        /// Justification to select before the inner instruction
+        justification: Option<Justification>,
+        /// Instruction printed inside of the group
+        inner: Box<Instruction>
+    }
 }
 
 /// Instruction addition
@@ -171,16 +236,108 @@ impl Instruction {
         matches!(self, Instruction::Text{..})
     }
 
+    /// Reverses the order of the instructions contained in a compound instruction
+    ///
+    /// Nested compound instructions get reversed as well, while any other instruction stays as it is. When the printer is in upside-down mode, the paper comes out rotated, so printing the reversed instruction keeps the content readable from top to bottom.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile, command::Command};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::command(Command::BoldOn) +
+    ///     Instruction::command(Command::Cut) +
+    ///     Instruction::command(Command::BoldOff);
+    ///
+    /// let mut target = Command::BoldOff.as_bytes();
+    /// target.append(&mut Command::Cut.as_bytes());
+    /// target.append(&mut Command::BoldOn.as_bytes());
+    /// assert_eq!(target, instruction.reversed().to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn reversed(self) -> Instruction {
+        match self {
+            Instruction::Compound{instructions} => Instruction::Compound {
+                instructions: instructions.into_iter().rev().map(Instruction::reversed).collect()
+            },
+            Instruction::Group{font, code_table, justification, inner} => Instruction::Group {
+                font,
+                code_table,
+                justification,
+                inner: Box::new(inner.reversed())
+            },
+            other => other
+        }
+    }
+
     /// Sends simple text to the printer.
     ///
     /// Straightfoward text printing. The `replacements` set specifies which contents of the string should be replaced in a per-impresion basis.
+    ///
+    /// The text gets wrapped by whitespaces according to the font's width. New lines in the content force a line break, so each paragraph gets wrapped on its own and empty lines are kept.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::{Command, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 20).build();
+    /// let instruction = Instruction::text("Thanks for your visit\n\nCome back soon", Font::FontA, Justification::Left, None);
+    ///
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// target.extend_from_slice(b"Thanks for your\nvisit\n\nCome back soon\n");
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    ///
+    /// The width is counted in characters, so text in code tables with characters beyond ascii wraps and aligns by what ends up on paper.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::{Command, CodeTable, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_font_width(Font::FontA, 12)
+    ///     .with_default_code_table(CodeTable::Katakana)
+    ///     .build();
+    /// let instruction = Instruction::text("ｱｲｳｴ ｱｲｳｴ", Font::FontA, Justification::Right, None);
+    ///
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// target.extend_from_slice(&[b' ', b' ', b' ', 0xb1, 0xb2, 0xb3, 0xb4, b' ', 0xb1, 0xb2, 0xb3, 0xb4, b'\n']);
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
     pub fn text<A: Into<String>>(content: A, font: Font, justification: Justification, replacements: Option<HashSet<String>>) -> Instruction {
         Instruction::Text {
             content: content.into(),
             markdown: false,
             font,
             justification,
-            replacements
+            replacements,
+            max_lines: None
+        }
+    }
+
+    /// Prints text as an image, drawn with a bundled bitmap font
+    ///
+    /// Useful for big or emphasized text on printers with poor font support, as the result looks the same on every printer that can print images. Each pixel of the 8x13 font takes `scale` by `scale` dots. The text gets wrapped by whitespaces if it does not fit in the paper, and characters outside of ascii are drawn as question marks.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let bytes = Instruction::text_as_image("TOTAL 5.00", 2, Justification::Center).to_vec(&printer_profile, None)?;
+    ///
+    /// // Each bitmap line spans the profile width, 384 dots
+    /// let header = bytes.windows(5).position(|window| window[0..3] == [0x1b, 0x2a, 0x01]).unwrap();
+    /// assert_eq!(384, bytes[header + 3] as usize + 256 * bytes[header + 4] as usize);
+    /// // And some dots are set
+    /// assert!(bytes[header + 5..header + 5 + 384].iter().any(|byte| *byte != 0));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn text_as_image<A: Into<String>>(content: A, scale: u8, justification: Justification) -> Instruction {
+        Instruction::TextImage {
+            content: content.into(),
+            scale: scale.max(1),
+            justification
         }
     }
 
@@ -188,14 +345,16 @@ impl Instruction {
     ///
     /// Allows markdown to be sent to the printer. Not everything is supported, so far the following list works (if the printer supports the corresponding fonts)
     ///  * Bold font, with **
-    ///  * Italics, with _
+    ///
+    /// Bold can span several words, or even lines. A marker without its closing pair gives back [WrongMarkdown](crate::Error::WrongMarkdown).
     pub fn markdown(content: String, font: Font, justification: Justification, replacements: Option<HashSet<String>>) -> Instruction {
         Instruction::Text {
             content,
             markdown: true,
             font,
             justification,
-            replacements
+            replacements,
+            max_lines: None
         }
     }
 
@@ -208,22 +367,112 @@ impl Instruction {
         })
     }
 
+    /// Prints an image given at printing time
+    ///
+    /// The image gets looked up by name in the [PrintData](crate::PrintData), see [add_image](crate::PrintDataBuilder::add_image). Keeps serialized templates small, as the image is not embedded, and allows the same template to print different logos.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, EscposImage, Justification, Error};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let logo = EscposImage::new(image::DynamicImage::new_rgba8(8, 8), 255, Justification::Left)?;
+    /// let instruction = Instruction::image_ref("logo");
+    ///
+    /// let print_data = PrintData::builder().add_image("logo", logo.clone()).build();
+    /// assert_eq!(
+    ///     Instruction::image(logo)?.to_vec(&printer_profile, None)?,
+    ///     instruction.to_vec(&printer_profile, Some(&print_data))?
+    /// );
+    ///
+    /// let print_data = PrintData::builder().build();
+    /// assert!(matches!(instruction.to_vec(&printer_profile, Some(&print_data)), Err(Error::NoImageFound(_))));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn image_ref<A: Into<String>>(name: A) -> Instruction {
+        Instruction::ImageRef{name: name.into()}
+    }
+
     /// Creates a new QR code that does not change through different print steps
     pub fn qr_code(content: String) -> Result<Instruction, Error> {
-        let code = QrCode::new(content.as_bytes()).unwrap();
+        Instruction::justified_qr_code(content, Justification::Center)
+    }
+
+    /// Creates a new static QR code, with a specific justification
+    pub fn justified_qr_code(content: String, justification: Justification) -> Result<Instruction, Error> {
+        let code = QrCode::new(content.as_bytes()).map_err(Error::QrError)?;
         // Render the bits into an image.
         let img = code.render::<image::Rgba<u8>>().build();
 
         let escpos_image = EscposImage::new(
             image::DynamicImage::ImageRgba8(img),//.write_to(&mut content, image::ImageOutputFormat::Png).unwrap();
             128,
-            Justification::Center
+            justification
         )?;
         
         Instruction::image(escpos_image)
     }
 
+    /// Creates a receipt with a QR code as footer
+    ///
+    /// The body gets printed with the given font, followed by an empty line, the QR code with the given justification, `feed` lines to reach the tear point, and a cut. If the printer profile supports native QR codes, the printer generates the code by itself. Otherwise, the code gets printed as an image. Content that does not fit in a QR code results in a [QrError](crate::Error::QrError).
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, Error, command::{Command, ErrorCorrection, Font, QrModel}};
+    /// let path = std::env::temp_dir().join("escpos-rs-qr-footer-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_native_qr(true).build())?.unwrap();
+    /// let instruction = Instruction::qr_footer("Thanks!", "https://example.com/order/1", Font::FontA, Justification::Center, 3)?;
+    /// printer.instruction(&instruction, None)?;
+    ///
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// // The body, and the empty line before the code
+    /// target.extend_from_slice(b"Thanks!\n\n");
+    /// target.append(&mut Command::SelectJustification{justification: Justification::Center}.as_bytes());
+    /// target.append(&mut Command::QrCode{content: "https://example.com/order/1".into(), model: QrModel::Model2, module_size: 6, error_correction: ErrorCorrection::M}.as_bytes());
+    /// target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+    /// target.extend_from_slice(b"\n\n\n");
+    /// target.append(&mut Command::Cut.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    ///
+    /// let too_long = "x".repeat(8000);
+    /// assert!(matches!(Instruction::qr_footer("Thanks!", too_long, Font::FontA, Justification::Center, 3), Err(Error::QrError(_))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn qr_footer<A: Into<String>, B: Into<String>>(body: A, qr_content: B, font: Font, justification: Justification, feed: u8) -> Result<Instruction, Error> {
+        let content = qr_content.into();
+        // Checked right away, as printers generating the code by themselves cannot report it
+        QrCode::new(content.as_bytes()).map_err(Error::QrError)?;
+        Ok(
+            Instruction::text(body, font, Justification::Left, None) +
+            Instruction::vspace(1) +
+            Instruction::StaticQrCode{content, justification} +
+            Instruction::vspace(feed) +
+            Instruction::cut()
+        )
+    }
+
     /// Creates a dynamic qr code instruction, which requires a string at printing time
+    ///
+    /// If the printer profile supports native QR codes, the printer generates the code by itself. Otherwise, the code gets printed as an image.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, command::{Command, ErrorCorrection, QrModel}};
+    ///
+    /// let instruction = Instruction::dynamic_qr_code("order");
+    /// let print_data = PrintData::builder().add_qr_code("order", "https://example.com").build();
+    /// let native = Command::QrCode{content: "https://example.com".into(), model: QrModel::Model2, module_size: 6, error_correction: ErrorCorrection::M}.as_bytes();
+    ///
+    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001).with_native_qr(true).build();
+    /// let bytes = instruction.to_vec(&printer_profile, Some(&print_data))?;
+    /// assert!(bytes.windows(native.len()).any(|window| window == native.as_slice()));
+    ///
+    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001).with_native_qr(false).build();
+    /// let bytes = instruction.to_vec(&printer_profile, Some(&print_data))?;
+    /// assert!(!bytes.windows(native.len()).any(|window| window == native.as_slice()));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
     pub fn dynamic_qr_code<A: Into<String>>(name: A) -> Instruction {
         Instruction::QRCode{name: name.into()}
     }
@@ -236,11 +485,24 @@ impl Instruction {
     }
 
     /// Creates a table with two columns.
+    ///
+    /// Cells that do not fit in the width get truncated, giving priority to the second column.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 10).build();
+    /// let print_data = PrintData::builder().add_duo_table("products", vec![("Milk".into(), "5.00".into())]).build();
+    /// let table = Instruction::duo_table("products", ("A rather long product header", "Price"), Font::FontA);
+    /// assert_eq!(b"A ratPrice\n----------\nMilk  5.00\n".to_vec(), table.to_vec(&printer_profile, Some(&print_data))?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
     pub fn duo_table<A: Into<String>, B: Into<String>, C: Into<String>>(name: A, header: (B, C), font: Font) -> Instruction {
         Instruction::DuoTable {
             name: name.into(),
             header: (header.0.into(), header.1.into()),
-            font
+            font,
+            code_tables: Vec::new()
         }
     }
 
@@ -248,10 +510,90 @@ impl Instruction {
     pub fn trio_table<A: Into<String>, B: Into<String>, C: Into<String>, D: Into<String>>(name: A, header: (B, C, D)) -> Instruction {
         Instruction::TrioTable {
             name: name.into(),
-            header: (header.0.into(), header.1.into(), header.2.into())
+            header: (header.0.into(), header.1.into(), header.2.into()),
+            code_tables: Vec::new()
         }
     }
 
+    /// Sets a code table for each column of a table
+    ///
+    /// Columns with a code table get wrapped by the corresponding `ESC t` commands, and their content gets encoded with it. After each of those cells, the profile's default code table is selected back. Columns without a code table (or beyond the length of the vector) use the printer's current code table. Only has an effect on two and three column tables.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, command::{Command, CodeTable, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::duo_table("products", ("Product", "Price"), Font::FontA)
+    ///     .with_column_code_tables(vec![None, Some(CodeTable::PC850)]);
+    /// let print_data = PrintData::builder()
+    ///     .add_duo_table("products", vec![("Milk".into(), "Ø 5.00".into())])
+    ///     .build();
+    /// let bytes = instruction.to_vec(&printer_profile, Some(&print_data))?;
+    ///
+    /// // The second cell of the row is surrounded by the code table commands, and encoded in PC850
+    /// let mut row = b"Milk".to_vec();
+    /// row.append(&mut Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes());
+    /// row.extend_from_slice(&[b' '; 22]);
+    /// row.push(0x9d);
+    /// row.extend_from_slice(b" 5.00");
+    /// row.append(&mut Command::SelectCodeTable{code_table: CodeTable::USA}.as_bytes());
+    /// row.push(b'\n');
+    /// assert!(bytes.ends_with(&row));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_column_code_tables(mut self, column_code_tables: Vec<Option<CodeTable>>) -> Instruction {
+        match &mut self {
+            Instruction::DuoTable{code_tables, ..} | Instruction::TrioTable{code_tables, ..} => {
+                *code_tables = column_code_tables;
+            },
+            _ => ()
+        }
+        self
+    }
+
+    /// Limits the number of lines a text instruction can take
+    ///
+    /// Text that wraps beyond the limit gets dropped, and the last printed line ends with an ellipsis. As the ellipsis character is not part of most code tables, three dots are used instead. Only has an effect on text instructions.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile, Justification, command::{Command, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 10).build();
+    /// let instruction = Instruction::text(
+    ///     "one two three four five six seven eight nine",
+    ///     Font::FontA,
+    ///     Justification::Left,
+    ///     None
+    /// ).with_max_lines(3);
+    ///
+    /// // Without the limit, the text takes 5 lines
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// target.extend_from_slice(b"one two\nthree four\nfive si...\n");
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    ///
+    /// // A word longer than the width takes a line of its own, and keeps its content
+    /// let long_word = "Supercalifragilisticexpialido";
+    /// let instruction = Instruction::text(long_word, Font::FontA, Justification::Left, None).with_max_lines(1);
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// target.extend_from_slice(b"Supercalifragilisticexpialido\n");
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    ///
+    /// let instruction = Instruction::text(format!("{} and more", long_word), Font::FontA, Justification::Left, None).with_max_lines(1);
+    /// let mut target = Command::SelectFont{font: Font::FontA}.as_bytes();
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// target.extend_from_slice(b"Superca...\n");
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_max_lines(mut self, lines: u8) -> Instruction {
+        if let Instruction::Text{max_lines, ..} = &mut self {
+            *max_lines = Some(lines);
+        }
+        self
+    }
+
     /// Creates a table with four columns
     ///
     /// Tables with four columns can be quite tight in 80mm printers, and unthinkable in 58mm ones or smaller. Use with caution!
@@ -263,6 +605,8 @@ impl Instruction {
     }
 
     /// Cuts the paper (if supported)
+    ///
+    /// For printers without a cutter, some lines get fed instead. See [with_cutter](crate::PrinterProfileBuilder::with_cutter).
     pub fn cut() -> Instruction {
         Instruction::Cut
     }
@@ -272,34 +616,532 @@ impl Instruction {
         Instruction::VSpace{lines}
     }
 
+    /// Repeats an instruction a number of times
+    ///
+    /// The result is a compound instruction, with one copy of the instruction per repetition.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, command::Font};
+    ///
+    /// let stars = Instruction::repeat(Instruction::text("* * *", Font::FontA, Justification::Center, None), 3);
+    /// match stars {
+    ///     Instruction::Compound{instructions} => {
+    ///         assert_eq!(3, instructions.len());
+    ///         assert!(instructions.iter().all(|instruction| instruction.is_text()));
+    ///     },
+    ///     _other => panic!("a compound instruction was expected")
+    /// }
+    /// ```
+    pub fn repeat(instruction: Instruction, times: usize) -> Instruction {
+        Instruction::Compound {
+            instructions: vec![instruction; times]
+        }
+    }
+
+    /// Repeats an instruction a number of times, with a separator between each copy
+    ///
+    /// The separator goes only between copies, not before the first one nor after the last one.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let slots = Instruction::repeat_with_separator(Instruction::vspace(2), 3, Instruction::cut());
+    /// let mut target = Vec::new();
+    /// for idx in 0..3 {
+    ///     if idx > 0 {
+    ///         target.append(&mut Instruction::cut().to_vec(&printer_profile, None)?);
+    ///     }
+    ///     target.extend_from_slice(b"\n\n");
+    /// }
+    /// assert_eq!(target, slots.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn repeat_with_separator(instruction: Instruction, times: usize, separator: Instruction) -> Instruction {
+        let mut instructions = Vec::with_capacity((2 * times).saturating_sub(1));
+        for idx in 0..times {
+            if idx > 0 {
+                instructions.push(separator.clone());
+            }
+            instructions.push(instruction.clone());
+        }
+        Instruction::Compound {
+            instructions
+        }
+    }
+
+    /// Creates an instruction that depends on a flag
+    ///
+    /// The value of the flag is looked up in the [PrintData](crate::PrintData) at printing time. If the flag is set, the `then` instruction gets printed, otherwise the `otherwise` instruction (if any) is printed instead. A missing flag results in an error.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::conditional(
+    ///     "discount",
+    ///     Instruction::vspace(2),
+    ///     Some(Instruction::vspace(1))
+    /// );
+    ///
+    /// let print_data = PrintData::builder().flag("discount", true).build();
+    /// assert_eq!(b"\n\n".to_vec(), instruction.to_vec(&printer_profile, Some(&print_data))?);
+    ///
+    /// let print_data = PrintData::builder().flag("discount", false).build();
+    /// assert_eq!(b"\n".to_vec(), instruction.to_vec(&printer_profile, Some(&print_data))?);
+    ///
+    /// // Without the flag, the instruction can't be printed
+    /// let print_data = PrintData::builder().build();
+    /// assert!(instruction.to_vec(&printer_profile, Some(&print_data)).is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn conditional<A: Into<String>>(flag: A, then: Instruction, otherwise: Option<Instruction>) -> Instruction {
+        Instruction::Conditional {
+            flag: flag.into(),
+            then: Box::new(then),
+            otherwise: otherwise.map(Box::new)
+        }
+    }
+
+    /// Creates a group, where the given settings apply only to the inner instruction
+    ///
+    /// The settings that are given get selected before the inner instruction, and restored after it (font A, the code table in use outside of the group and left justification), so they do not leak into the rest of the print. The text of the inner instruction, including the cells of duo and trio tables, gets encoded with the group's code table. Useful for self-contained sub-templates.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile, command::{Command, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::group(Some(Font::FontB), None, None, Instruction::vspace(1));
+    ///
+    /// let mut target = Command::SelectFont{font: Font::FontB}.as_bytes();
+    /// target.push(b'\n');
+    /// target.append(&mut Command::SelectFont{font: Font::FontA}.as_bytes());
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::{Command, CodeTable, Font}};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let text = Instruction::text("Ø Á", Font::FontA, Justification::Left, None);
+    /// let instruction = Instruction::group(None, Some(CodeTable::PC850), None, text);
+    ///
+    /// let mut target = Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes();
+    /// target.append(&mut Command::SelectFont{font: Font::FontA}.as_bytes());
+    /// target.append(&mut Command::Reset.as_bytes());
+    /// // In PC850, Ø is 0x9d and Á is 0xb5
+    /// target.extend_from_slice(&[0x9d, b' ', 0xb5, b'\n']);
+    /// target.append(&mut Command::SelectCodeTable{code_table: CodeTable::USA}.as_bytes());
+    /// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn group(font: Option<Font>, code_table: Option<CodeTable>, justification: Option<Justification>, inner: Instruction) -> Instruction {
+        Instruction::Group {
+            font,
+            code_table,
+            justification,
+            inner: Box::new(inner)
+        }
+    }
+
+    /// Renders a preview of the print
+    ///
+    /// The instruction gets rasterized into an image with the profile's width, approximating what the printer would output. Text is drawn with a bundled bitmap font, so the preview does not reflect the actual printer fonts. Requires the `preview` feature.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::Font};
+    /// use image::GenericImageView;
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::text("Hello, world!", Font::FontA, Justification::Center, None);
+    /// let preview = instruction.render_preview(&printer_profile, None)?;
+    /// assert_eq!(384, preview.width());
+    /// assert!(preview.height() > 0);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    #[cfg(feature = "preview")]
+    pub fn render_preview(&self, printer_profile: &PrinterProfile, print_data: Option<&PrintData>) -> Result<image::DynamicImage, Error> {
+        let bytes = self.to_vec(printer_profile, print_data)?;
+        Ok(preview::render(&bytes, printer_profile))
+    }
+
+    /// Serializes the instruction to a compact binary format
+    ///
+    /// Images are kept as raw bytes instead of base64, so templates with images take less room and are faster to read than their json counterpart, which stays as the human readable option. The format is [CBOR](https://cbor.io). Requires the `binary` feature.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, EscposImage, Justification, PrinterProfile, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let logo = EscposImage::new(image::DynamicImage::new_rgba8(64, 16), 255, Justification::Center)?;
+    /// let template = Instruction::image(logo)?
+    ///     + Instruction::text("Thanks for your visit", Font::FontA, Justification::Center, None)
+    ///     + Instruction::cut();
+    ///
+    /// let bytes = template.to_bytes()?;
+    /// let json = serde_json::to_string(&template).unwrap();
+    /// assert!(bytes.len() < json.len());
+    ///
+    /// let from_bytes = Instruction::from_bytes(&bytes)?;
+    /// let from_json: Instruction = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(from_json.to_vec(&printer_profile, None)?, from_bytes.to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    #[cfg(feature = "binary")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(self, &mut bytes).map_err(|e| Error::BinaryError(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Deserializes an instruction from the binary format of [to_bytes](Instruction::to_bytes)
+    ///
+    /// Requires the `binary` feature.
+    #[cfg(feature = "binary")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Instruction, Error> {
+        ciborium::de::from_reader(bytes).map_err(|e| Error::BinaryError(e.to_string()))
+    }
+
+    /// Estimates how many text lines the instruction takes on paper
+    ///
+    /// Wrapped text lines, table rows (including headers and division lines) and vertical spaces get counted as they would be printed. Images and qr codes are converted to the equivalent number of text lines, with the default line spacing, so the result is just an approximation.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrintData, PrinterProfile, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::text("This text is long enough to take two lines", Font::FontA, Justification::Left, None) +
+    ///     Instruction::duo_table("products", ("Product", "Price"), Font::FontA) +
+    ///     Instruction::vspace(2);
+    /// let print_data = PrintData::builder()
+    ///     .add_duo_table("products", vec![
+    ///         ("Milk".into(), "5.00".into()),
+    ///         ("Eggs".into(), "3.00".into()),
+    ///         ("Bread".into(), "2.50".into())
+    ///     ])
+    ///     .build();
+    ///
+    /// // 2 lines of text, header and division line, 3 rows, and 2 empty lines
+    /// assert_eq!(9, instruction.estimate_lines(&printer_profile, Some(&print_data))?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn estimate_lines(&self, printer_profile: &PrinterProfile, print_data: Option<&PrintData>) -> Result<u32, Error> {
+        Ok(match self {
+            Instruction::Compound{instructions} => {
+                let mut lines = 0;
+                for instruction in instructions {
+                    lines += instruction.estimate_lines(printer_profile, print_data)?;
+                }
+                lines
+            },
+            Instruction::Command{command} => match command {
+                Command::FeedLines{lines} => *lines as u32,
+                Command::QrCode{content, module_size, ..} => native_qr_lines(content, *module_size),
+                _other => 0
+            },
+            Instruction::VSpace{lines} => *lines as u32,
+            Instruction::Text{content, markdown, font, replacements, max_lines, ..} => {
+                let width = match printer_profile.columns_per_font.get(font) {
+                    Some(w) => *w,
+                    None => return Err(Error::NoWidth)
+                };
+                text_lines(content, *markdown, width, replacements, max_lines, print_data)?.len() as u32
+            },
+            Instruction::TextImage{content, scale, justification} => image_lines(&text_image(content, *scale, justification, printer_profile.width)?, printer_profile)?,
+            Instruction::DuoTable{name, ..} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let tables = print_data.duo_tables.as_ref().ok_or(Error::NoTables)?;
+                let table = tables.get(name).ok_or_else(|| Error::NoTableFound(name.clone()))?;
+                2 + table.len() as u32
+            },
+            Instruction::TrioTable{name, ..} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let tables = print_data.trio_tables.as_ref().ok_or(Error::NoTables)?;
+                let table = tables.get(name).ok_or_else(|| Error::NoTableFound(name.clone()))?;
+                2 + table.len() as u32
+            },
+            Instruction::QuadTable{name, ..} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let tables = print_data.quad_tables.as_ref().ok_or(Error::NoTables)?;
+                let table = tables.get(name).ok_or_else(|| Error::NoTableFound(name.clone()))?;
+                // Each row takes two lines
+                2 + 2 * table.len() as u32
+            },
+            Instruction::Image{image} => image_lines(image, printer_profile)?,
+            Instruction::ImageRef{name} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let image = print_data.images.as_ref()
+                    .and_then(|images| images.get(name))
+                    .ok_or_else(|| Error::NoImageFound(name.clone()))?;
+                image_lines(image, printer_profile)?
+            },
+            Instruction::QRCode{name} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let qr_contents = print_data.qr_contents.as_ref().ok_or(Error::NoQrContents)?;
+                let qr_content = qr_contents.get(name).ok_or_else(|| Error::NoQrContent(name.clone()))?;
+                if printer_profile.supports_native_qr {
+                    native_qr_lines(qr_content, NATIVE_QR_MODULE_SIZE)
+                } else {
+                    Instruction::qr_code(qr_content.clone())?.estimate_lines(printer_profile, Some(print_data))?
+                }
+            },
+            Instruction::StaticQrCode{content, justification} => {
+                if printer_profile.supports_native_qr {
+                    native_qr_lines(content, NATIVE_QR_MODULE_SIZE)
+                } else {
+                    Instruction::justified_qr_code(content.clone(), justification.clone())?.estimate_lines(printer_profile, print_data)?
+                }
+            },
+            Instruction::Cut => 0,
+            Instruction::Conditional{flag, then, otherwise} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let value = print_data.flags.as_ref()
+                    .and_then(|flags| flags.get(flag))
+                    .ok_or_else(|| Error::NoFlagFound(flag.clone()))?;
+                if *value {
+                    then.estimate_lines(printer_profile, Some(print_data))?
+                } else if let Some(otherwise) = otherwise {
+                    otherwise.estimate_lines(printer_profile, Some(print_data))?
+                } else {
+                    0
+                }
+            },
+            Instruction::Group{inner, ..} => inner.estimate_lines(printer_profile, print_data)?
+        })
+    }
+
+    /// Splits the instruction in pages, with a cut between them
+    ///
+    /// The instruction gets walked with the [line estimate](Instruction::estimate_lines), and a [cut](Instruction::cut) gets inserted each time a page would exceed `lines_per_page` (for printers without a cutter, cuts become a few line feeds). Plain text that does not fit gets split by lines, while the rest of the instructions (images, tables, groups and markdown text) move to the next page as a whole. Cuts already present in the instruction start a new page as well.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let report: Vec<String> = (1..=25).map(|idx| format!("Line {}", idx)).collect();
+    /// let instruction = Instruction::text(report.join("\n"), Font::FontA, Justification::Left, None);
+    /// assert_eq!(25, instruction.estimate_lines(&printer_profile, None)?);
+    ///
+    /// let paginated = instruction.paginate(10, &printer_profile, None)?;
+    /// match &paginated {
+    ///     Instruction::Compound{instructions} => {
+    ///         let cuts: Vec<usize> = instructions.iter().enumerate().filter(|(_, instruction)| matches!(instruction, Instruction::Cut)).map(|(idx, _)| idx).collect();
+    ///         // After the 10th and the 20th line
+    ///         assert_eq!(vec![10, 21], cuts);
+    ///     },
+    ///     _other => panic!("a compound instruction was expected")
+    /// }
+    /// assert_eq!(25, paginated.estimate_lines(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn paginate(&self, lines_per_page: u32, printer_profile: &PrinterProfile, print_data: Option<&PrintData>) -> Result<Instruction, Error> {
+        let lines_per_page = lines_per_page.max(1);
+        let mut leaves = Vec::new();
+        self.flatten_into(&mut leaves);
+
+        let mut instructions = Vec::new();
+        let mut used = 0;
+        for leaf in leaves {
+            if let Instruction::Cut = leaf {
+                instructions.push(leaf);
+                used = 0;
+                continue;
+            }
+            let lines = leaf.estimate_lines(printer_profile, print_data)?;
+            let pieces = match &leaf {
+                // Plain text that does not fit gets split by lines
+                Instruction::Text{content, markdown: false, font, justification, replacements, max_lines} if used + lines > lines_per_page && lines > 1 => {
+                    let width = *printer_profile.columns_per_font.get(font).ok_or(Error::NoWidth)?;
+                    text_lines(content, false, width, replacements, max_lines, print_data)?.into_iter().map(|line| {
+                        // Empty text prints nothing, so blank lines become a line feed
+                        if line.trim().is_empty() {
+                            (Instruction::vspace(1), 1)
+                        } else {
+                            (Instruction::text(line, font.clone(), justification.clone(), None), 1)
+                        }
+                    }).collect()
+                },
+                _other => vec![(leaf, lines)]
+            };
+            for (piece, lines) in pieces {
+                if used > 0 && used + lines > lines_per_page {
+                    instructions.push(Instruction::Cut);
+                    used = 0;
+                }
+                instructions.push(piece);
+                used += lines;
+            }
+        }
+        Ok(Instruction::Compound{instructions})
+    }
+
+    // Auxiliar function to collect the instructions inside of compound ones
+    fn flatten_into(&self, target: &mut Vec<Instruction>) {
+        match self {
+            Instruction::Compound{instructions} => for instruction in instructions {
+                instruction.flatten_into(target);
+            },
+            other => target.push(other.clone())
+        }
+    }
+
     /// Main serialization function
     ///
     /// This function turns the instruction structure into the sequence of bytes required to print the information, according to the ESCP/POS protocol. [PrintData](crate::PrintData) might be required if some of the information for printing is dynamic.
-    pub(crate) fn to_vec(&self, printer_profile: &PrinterProfile, print_data: Option<&PrintData>) -> Result<Vec<u8>, Error> {
+    ///
+    /// Text lines do not carry spaces at their end, as they would be invisible anyway.
+    ///
+    /// Empty parts are safe to use in templates: empty text produces no bytes (not even the font selection), an empty compound instruction is a no-op, and a table registered with no rows prints just its header and the divider. A table that was not registered at all is still an error.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrintData, PrinterProfile, Error, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 16).build();
+    /// assert!(Instruction::text("", Font::FontA, Justification::Center, None).to_vec(&printer_profile, None)?.is_empty());
+    /// assert!(Instruction::Compound{instructions: vec![]}.to_vec(&printer_profile, None)?.is_empty());
+    ///
+    /// let print_data = PrintData::builder().add_duo_table("extras", vec![]).build();
+    /// let table = Instruction::duo_table("extras", ("Extra", "Price"), Font::FontA);
+    /// assert_eq!(b"Extra      Price\n----------------\n".to_vec(), table.to_vec(&printer_profile, Some(&print_data))?);
+    /// let table = Instruction::duo_table("discounts", ("Discount", "Amount"), Font::FontA);
+    /// assert!(matches!(table.to_vec(&printer_profile, Some(&print_data)), Err(Error::NoTableFound(_))));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::Font};
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 16).build();
+    /// let paragraph = "A paragraph that wraps into a few lines of text";
+    /// for justification in vec![Justification::Left, Justification::Center] {
+    ///     let bytes = Instruction::text(paragraph, Font::FontA, justification, None).to_vec(&printer_profile, None)?;
+    ///     assert!(bytes.windows(2).all(|pair| pair != b" \n"));
+    /// }
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn to_vec(&self, printer_profile: &PrinterProfile, print_data: Option<&PrintData>) -> Result<Vec<u8>, Error> {
+        self.to_vec_with_handler(printer_profile, print_data, &printer_profile.default_code_table, None)
+    }
+
+    /// Renders the instruction as plain text lines of the given width
+    ///
+    /// Used to lay out instructions next to each other. Only text, vertical space, line feeds and their compound, conditional and group wrappers can be rendered; markdown markers get dropped, and fonts are ignored as the width is given. Anything else results in [UnsupportedForLayout](crate::Error::UnsupportedForLayout).
+    pub(crate) fn plain_lines(&self, width: u8, print_data: Option<&PrintData>) -> Result<Vec<String>, Error> {
+        let lines = match self {
+            Instruction::Compound{instructions} => {
+                let mut lines = Vec::new();
+                for instruction in instructions {
+                    lines.append(&mut instruction.plain_lines(width, print_data)?);
+                }
+                lines
+            },
+            Instruction::VSpace{lines} | Instruction::Command{command: Command::FeedLines{lines}} => vec![String::new(); *lines as usize],
+            Instruction::Text{content, markdown, justification, replacements, max_lines, ..} => {
+                text_lines(content, *markdown, width, replacements, max_lines, print_data)?.into_iter().map(|line| {
+                    let line = if *markdown { line.replace(BOLD_MARKER, "") } else { line };
+                    match justification {
+                        Justification::Left => line,
+                        Justification::Right => format!("{:>1$}", line, width as usize),
+                        Justification::Center => format!("{:^1$}", line, width as usize).trim_end_matches(' ').to_string()
+                    }
+                }).collect()
+            },
+            Instruction::Conditional{flag, then, otherwise} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let value = print_data.flags.as_ref()
+                    .and_then(|flags| flags.get(flag))
+                    .ok_or_else(|| Error::NoFlagFound(flag.clone()))?;
+                if *value {
+                    then.plain_lines(width, Some(print_data))?
+                } else if let Some(otherwise) = otherwise {
+                    otherwise.plain_lines(width, Some(print_data))?
+                } else {
+                    Vec::new()
+                }
+            },
+            Instruction::Group{inner, ..} => inner.plain_lines(width, print_data)?,
+            _other => return Err(Error::UnsupportedForLayout)
+        };
+        Ok(lines)
+    }
+
+    /// Serialization function, reporting diagnostics to the given handler instead of the log
+    ///
+    /// Text gets encoded with the given code table, which should be the one the printer has selected.
+    pub(crate) fn to_vec_with_handler(&self, printer_profile: &PrinterProfile, print_data: Option<&PrintData>, code_table: &CodeTable, handler: Option<&dyn Fn(Diagnostic)>) -> Result<Vec<u8>, Error> {
         let mut target = Vec::new();
         match self {
             Instruction::Compound{instructions} => {
                 for instruction in instructions {
-                    target.append(&mut instruction.to_vec(printer_profile, print_data)?);
+                    target.append(&mut instruction.to_vec_with_handler(printer_profile, print_data, code_table, handler)?);
                 }
             },
             Instruction::Cut => {
-                target.extend_from_slice(&Command::Cut.as_bytes());
+                target.extend_from_slice(&printer_profile.cut_bytes());
+            },
+            Instruction::Conditional{flag, then, otherwise} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let value = print_data.flags.as_ref()
+                    .and_then(|flags| flags.get(flag))
+                    .ok_or_else(|| Error::NoFlagFound(flag.clone()))?;
+                if *value {
+                    target.append(&mut then.to_vec_with_handler(printer_profile, Some(print_data), code_table, handler)?);
+                } else if let Some(otherwise) = otherwise {
+                    target.append(&mut otherwise.to_vec_with_handler(printer_profile, Some(print_data), code_table, handler)?);
+                }
             },
             Instruction::Command{command} => {
                 target.append(&mut command.as_bytes());
-            }
+            },
+            Instruction::Group{font, code_table: group_code_table, justification, inner} => {
+                if let Some(font) = font {
+                    target.append(&mut printer_profile.font_bytes(font));
+                }
+                if let Some(group_code_table) = group_code_table {
+                    target.append(&mut Command::SelectCodeTable{code_table: group_code_table.clone()}.as_bytes());
+                }
+                if let Some(justification) = justification {
+                    target.append(&mut Command::SelectJustification{justification: justification.clone()}.as_bytes());
+                }
+                // The inner text gets encoded with the table of the group
+                let inner_code_table = group_code_table.as_ref().unwrap_or(code_table);
+                target.append(&mut inner.to_vec_with_handler(printer_profile, print_data, inner_code_table, handler)?);
+                // Restoring, in reverse order
+                if justification.is_some() {
+                    target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+                }
+                if group_code_table.is_some() {
+                    target.append(&mut Command::SelectCodeTable{code_table: code_table.clone()}.as_bytes());
+                }
+                if font.is_some() {
+                    target.append(&mut printer_profile.font_bytes(&Font::FontA));
+                }
+            },
             Instruction::VSpace{lines} => {
                 target.append(&mut vec![b'\n'; *lines as usize])
             },
             Instruction::Image{image} => {
-                target.extend_from_slice(&image.feed(printer_profile.width));
+                target.append(&mut image.feed_for_profile(printer_profile, handler)?);
+            },
+            Instruction::ImageRef{name} => {
+                let print_data = print_data.ok_or(Error::NoPrintData)?;
+                let image = print_data.images.as_ref()
+                    .and_then(|images| images.get(name))
+                    .ok_or_else(|| Error::NoImageFound(name.clone()))?;
+                target.append(&mut image.feed_for_profile(printer_profile, handler)?);
             },
             Instruction::QRCode{name} => {
                 let print_data = print_data.ok_or(Error::NoPrintData)?;
                 if let Some(qr_contents) = &print_data.qr_contents {
                     if let Some(qr_content) = qr_contents.get(name) {
-                        target.extend_from_slice(&Instruction::qr_code(qr_content.clone())?.to_vec(printer_profile, Some(print_data))?)
+                        if printer_profile.supports_native_qr {
+                            target.append(&mut Command::SelectJustification{justification: Justification::Center}.as_bytes());
+                            target.append(&mut Command::QrCode{content: qr_content.clone(), model: QrModel::Model2, module_size: NATIVE_QR_MODULE_SIZE, error_correction: ErrorCorrection::M}.as_bytes());
+                            target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+                        } else {
+                            target.extend_from_slice(&Instruction::qr_code(qr_content.clone())?.to_vec_with_handler(printer_profile, Some(print_data), code_table, handler)?)
+                        }
                     } else {
                         return Err(Error::NoQrContent(name.clone()))
                     }
@@ -307,93 +1149,75 @@ impl Instruction {
                     return Err(Error::NoQrContents)
                 }
             },
+            Instruction::StaticQrCode{content, justification} => {
+                if printer_profile.supports_native_qr {
+                    target.append(&mut Command::SelectJustification{justification: justification.clone()}.as_bytes());
+                    target.append(&mut Command::QrCode{content: content.clone(), model: QrModel::Model2, module_size: NATIVE_QR_MODULE_SIZE, error_correction: ErrorCorrection::M}.as_bytes());
+                    target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+                } else {
+                    target.append(&mut Instruction::justified_qr_code(content.clone(), justification.clone())?.to_vec_with_handler(printer_profile, print_data, code_table, handler)?);
+                }
+            },
             // Text serialization for the printer
-            Instruction::Text{content, markdown, font, justification, replacements: self_replacements} => {
-                // We setup the font, mainly
-                target.append(&mut Command::SelectFont{font: font.clone()}.as_bytes());
-
+            Instruction::Text{content, markdown, font, justification, replacements, max_lines} => {
                 // We extract the width for this font
-                let width = match printer_profile.columns_per_font.get(&font) {
+                let width = match printer_profile.columns_per_font.get(font) {
                     Some(w) => *w,
                     None => return Err(Error::NoWidth)
                 };
 
-                let mut replaced_string = content.clone();
-                // First of all, we replace all the replacements
-                if let Some(self_replacements) = &self_replacements {
-                    if !self_replacements.is_empty() {
-                        let print_data = print_data.ok_or(Error::NoPrintData)?;
-
-                        for key in self_replacements.iter() {
-                            if let Some(replacement) = print_data.replacements.get(key) {
-                                replaced_string = replaced_string.as_str().replace(key, replacement);
-                            } else {
-                                return Err(Error::NoReplacementFound(key.clone()))
-                            }
-                        }
-                    }
+                let lines = text_lines(content, *markdown, width, replacements, max_lines, print_data)?;
+                // Empty text does not touch the printer at all
+                if lines.is_empty() {
+                    return Ok(target);
                 }
 
-                // Now, we demarkdownize the string
-                let demarkdown_string = if *markdown {
-                    // We tokenize the string
-                    let mut _tmp = String::new();
-                    panic!("Not implemented the markdown thingy, is too hard!");
-                } else {
-                    replaced_string
-                };
+                // We setup the font, mainly
+                target.append(&mut printer_profile.font_bytes(font));
 
-                // Now, we tokenize by spaces, using the width and justification
+                // Now we actually format the lines, using the justification
                 let mut result = Command::Reset.as_bytes();
-                // Line to control the text
-                let mut line = String::new();
-                let tokens = demarkdown_string.split_whitespace();
-                let mut width_count = 0;
-                
-                for token in tokens {
-                    if width_count + token.len() + 1 > (width as usize) {
-                        // We have to create a new line, this does not fit.
-                        width_count = token.len();
-                        // Now we actually format the line
-                        let mut tmp = match justification {
+                if *markdown {
+                    let mut bold = false;
+                    for line in lines {
+                        // The padding depends on what ends up on paper, not on the markers
+                        let free = (width as usize).saturating_sub(visible_len(&line, true));
+                        let padding = match justification {
+                            Justification::Left => 0,
+                            Justification::Right => free,
+                            Justification::Center => free / 2
+                        };
+                        result.append(&mut vec![b' '; padding]);
+                        result.append(&mut markdown_line(&line, &mut bold, code_table)?);
+                        result.push(b'\n');
+                    }
+                } else {
+                    for line in lines {
+                        let tmp = match justification {
                             Justification::Left => format!("{}\n", line),
                             Justification::Right => format!("{:>1$}\n", line, width as usize),
-                            Justification::Center => format!("{:^1$}\n", line, width as usize)
-                        }.into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?;
-                        result.append(&mut tmp);
-
-                        // And we start the new line
-                        line = token.to_string();
-                    } else {
-                        width_count += token.len();
-                        if !line.is_empty() {
-                            width_count += 1;
-                            line += " ";
-                        }
-                        line += token;
+                            // The padding at the right is invisible, so it doesn't get sent
+                            Justification::Center => format!("{}\n", format!("{:^1$}", line, width as usize).trim_end_matches(' '))
+                        };
+                        result.append(&mut code_table.encode(tmp)?);
                     }
                 }
-
-                // Last, we deal with the last line
-                if !line.is_empty() {
-                    let mut tmp = match justification {
-                        Justification::Left => format!("{}\n", line),
-                        Justification::Right => format!("{:>1$}\n", line, width as usize),
-                        Justification::Center => format!("{:^1$}\n", line, width as usize)
-                    }.into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?;
-                    result.append(&mut tmp);
-                }
                 
                 target.append(&mut result);
             },
-            Instruction::DuoTable{name, header, font} => {
+            Instruction::TextImage{content, scale, justification} => {
+                let mut escpos_image = text_image(content, *scale, justification, printer_profile.width)?;
+                escpos_image.cache_for(printer_profile.width);
+                target.append(&mut escpos_image.feed_for_profile(printer_profile, handler)?);
+            },
+            Instruction::DuoTable{name, header, font, code_tables} => {
                 // We extract the width for this font
-                let width = match printer_profile.columns_per_font.get(&font) {
+                let width = match printer_profile.columns_per_font.get(font) {
                     Some(w) => *w,
                     None => return Err(Error::NoWidth)
                 };
                 //First, the headers
-                target.extend_from_slice(&format!("{}{:>2$}\n", header.0, header.1, (width as usize) - header.0.len()).into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?);
+                target.append(&mut duo_row(header, width as usize, code_tables, code_table)?);
 
                 // Now, the line too
                 target.append(&mut vec![b'-'; width as usize]);
@@ -405,7 +1229,7 @@ impl Instruction {
                 if let Some(tables) = &print_data.duo_tables {
                     if let Some(table) = tables.get(name) {
                         for row in table {
-                            target.extend_from_slice(&format!("{}{:>2$}\n", row.0, row.1, (width as usize) - row.0.len()).into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?)
+                            target.append(&mut duo_row(row, width as usize, code_tables, code_table)?);
                         }
                     } else {
                         return Err(Error::NoTableFound(name.clone()))
@@ -414,7 +1238,7 @@ impl Instruction {
                     return Err(Error::NoTables)
                 }
             },
-            Instruction::TrioTable{name, header} => {
+            Instruction::TrioTable{name, header, code_tables} => {
                 // First, we will determine the proper alignment for the middle component
                 let print_data = print_data.ok_or(Error::NoPrintData)?;
 
@@ -457,19 +1281,12 @@ impl Instruction {
                 } else {
                     // Unluckily, we try to go for thirds
                     let third = width / 3;
-                    if width % 3 == 0 {
-                        (third, third)
-                    } else if width % 3 == 1 {
-                        (third, third)
-                    } else {
-                        (third, third)
-                    }
+                    (third, third)
                 };
 
                 // We go with the headers
-                target.extend_from_slice(
-                    &trio_row(header.clone(), width, max_left, max_right)
-                .into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?);
+                let cells = trio_cells(header.clone(), width, max_left, max_right);
+                target.append(&mut encode_cells(&[cells.0, cells.1, cells.2], code_tables, code_table)?);
 
                 // Now, the line too
                 target.append(&mut vec![b'-'; width]);
@@ -479,9 +1296,8 @@ impl Instruction {
                 if let Some(tables) = &print_data.trio_tables {
                     if let Some(table) = tables.get(name) {
                         for row in table {
-                            target.extend_from_slice(
-                                &trio_row(row.clone(), width, max_left, max_right)
-                            .into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?);
+                            let cells = trio_cells(row.clone(), width, max_left, max_right);
+                            target.append(&mut encode_cells(&[cells.0, cells.1, cells.2], code_tables, code_table)?);
                         }
                     } else {
                         return Err(Error::NoTableFound(name.clone()))
@@ -533,13 +1349,7 @@ impl Instruction {
                     // Unluckily, we try to go for thirds
                     let third = width / 3;
                     // This spacing algorithm requires work
-                    if width % 3 == 0 {
-                        (third, third)
-                    } else if width % 3 == 1 {
-                        (third, third)
-                    } else {
-                        (third, third)
-                    }
+                    (third, third)
                 };
 
                 // We go with the headers
@@ -556,9 +1366,9 @@ impl Instruction {
                     if let Some(table) = tables.get(name) {
                         for row in table {
                             // First row
-                            target.extend_from_slice(&Command::SelectFont{font: Font::FontB}.as_bytes());
+                            target.extend_from_slice(&printer_profile.font_bytes(&Font::FontB));
                             target.extend_from_slice(&format!("{}\n", row.0).into_cp437(&CP437_CONTROL).map_err(|_| Error::Encoding)?);
-                            target.extend_from_slice(&Command::SelectFont{font: Font::FontA}.as_bytes());
+                            target.extend_from_slice(&printer_profile.font_bytes(&Font::FontA));
                             // Now the three columns
                             target.extend_from_slice(
                                 &trio_row((row.1.clone(), row.2.clone(), row.3.clone()), width, max_left, max_right)
@@ -576,8 +1386,175 @@ impl Instruction {
     }
 }
 
+// Auxiliar function to draw text with the bitmap font, in an image as wide as the paper
+fn text_image(content: &str, scale: u8, justification: &Justification, width: u16) -> Result<EscposImage, Error> {
+    let scale = (scale as u32).max(1);
+    let (glyph_width, glyph_height) = (bitmap_font::GLYPH_WIDTH * scale, bitmap_font::GLYPH_HEIGHT * scale);
+    let columns = ((width as u32) / glyph_width).clamp(1, 255) as u8;
+    let lines = text_lines(content, false, columns, &None, &None, None)?;
+
+    let mut canvas = image::RgbaImage::new(width as u32, (lines.len().max(1) as u32) * glyph_height);
+    for (line_idx, line) in lines.iter().enumerate() {
+        let line_width = (line.chars().count() as u32) * glyph_width;
+        let x_offset = match justification {
+            Justification::Left => 0,
+            Justification::Center => (width as u32).saturating_sub(line_width) / 2,
+            Justification::Right => (width as u32).saturating_sub(line_width)
+        };
+        for (char_idx, c) in line.chars().enumerate() {
+            let glyph = bitmap_font::glyph(c);
+            for y in 0..glyph_height {
+                let row = glyph[(y / scale) as usize];
+                for x in 0..glyph_width {
+                    let canvas_x = x_offset + (char_idx as u32) * glyph_width + x;
+                    if row & (0x80 >> (x / scale)) != 0 && canvas_x < width as u32 {
+                        canvas.put_pixel(canvas_x, (line_idx as u32) * glyph_height + y, image::Rgba([0, 0, 0, 255]));
+                    }
+                }
+            }
+        }
+    }
+
+    EscposImage::new(image::DynamicImage::ImageRgba8(canvas), 255, Justification::Left)
+}
+
+// Auxiliar function to convert the height of an image into text lines
+fn image_lines(image: &EscposImage, printer_profile: &PrinterProfile) -> Result<u32, Error> {
+    // Same scaling as the one used for printing, each band of bytes covers 24 dots of paper (8 dots printed 3 times taller, or 24 dots)
+    let image_mode = image.printing_mode(printer_profile)?;
+    let height = ((printer_profile.width as u32) * image.height()) / (image_mode.dot_height() * image.width().max(1));
+    let dots = height.div_ceil(image_mode.band_height()) * BITMAP_ROW_HEIGHT;
+    Ok(dots.div_ceil(DOTS_PER_LINE))
+}
+
+// Auxiliar function to convert the height of a natively printed qr code into text lines
+fn native_qr_lines(content: &str, module_size: u8) -> u32 {
+    match QrCode::new(content.as_bytes()) {
+        Ok(code) => {
+            let dots = (code.width() as u32) * (module_size as u32);
+            dots.div_ceil(DOTS_PER_LINE)
+        },
+        Err(_) => 0
+    }
+}
+
+// Auxiliar function to replace, and split in lines the content of a text instruction
+fn text_lines(content: &str, markdown: bool, width: u8, self_replacements: &Option<HashSet<String>>, max_lines: &Option<u8>, print_data: Option<&PrintData>) -> Result<Vec<String>, Error> {
+    let mut replaced_string = content.to_string();
+    // First of all, we replace all the replacements
+    if let Some(self_replacements) = self_replacements {
+        if !self_replacements.is_empty() {
+            let print_data = print_data.ok_or(Error::NoPrintData)?;
+
+            for key in self_replacements.iter() {
+                if let Some(replacement) = print_data.replacements.get(key) {
+                    replaced_string = replaced_string.as_str().replace(key, replacement);
+                } else {
+                    return Err(Error::NoReplacementFound(key.clone()))
+                }
+            }
+        }
+    }
+
+    // Markdown markers stay in the text, they are turned into commands when serializing. An unclosed marker is an error.
+    if markdown && !replaced_string.matches(BOLD_MARKER).count().is_multiple_of(2) {
+        return Err(Error::WrongMarkdown);
+    }
+    let demarkdown_string = replaced_string;
+
+    // Now, we tokenize by spaces, using the width
+    let mut lines = Vec::new();
+    // Text with nothing to print produces no lines at all
+    if demarkdown_string.trim().is_empty() {
+        return Ok(lines);
+    }
+    // Explicit new lines force a break, so each paragraph gets wrapped on its own. A single trailing new line does not add an empty line.
+    let paragraphs = demarkdown_string.strip_suffix('\n').unwrap_or(&demarkdown_string);
+    for paragraph in paragraphs.split('\n') {
+        // Line to control the text
+        let mut line = String::new();
+        // Tabs are kept, so they can move to the printer's tab stops
+        let tokens = paragraph.split(|c: char| c.is_whitespace() && c != '\t').filter(|token| !token.is_empty());
+        let mut width_count = 0;
+
+        for token in tokens {
+            // Markers take no room on paper
+            let token_len = visible_len(token, markdown);
+            // The separating space is only needed after another token, and a token too long for the width goes alone in its line
+            let separator = if line.is_empty() { 0 } else { 1 };
+            if !line.is_empty() && width_count + separator + token_len > (width as usize) {
+                // We have to create a new line, this does not fit.
+                width_count = token_len;
+                lines.push(line);
+
+                // And we start the new line
+                line = token.to_string();
+            } else {
+                width_count += separator + token_len;
+                if separator > 0 {
+                    line += " ";
+                }
+                line += token;
+            }
+        }
+
+        // Last, we deal with the last line, which is kept even if empty, as a blank line
+        lines.push(line);
+    }
+
+    // If there are more lines than allowed, the last one gets an ellipsis
+    if let Some(max_lines) = max_lines {
+        let max_lines = *max_lines as usize;
+        if lines.len() > max_lines {
+            lines.truncate(max_lines);
+            if let Some(last) = lines.last_mut() {
+                ellipsize(last, width as usize);
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+// Marker that toggles bold text in markdown instructions
+const BOLD_MARKER: &str = "**";
+
+// Auxiliar function to count the characters that end up on paper
+fn visible_len(text: &str, markdown: bool) -> usize {
+    if markdown {
+        text.replace(BOLD_MARKER, "").chars().count()
+    } else {
+        text.chars().count()
+    }
+}
+
+// Auxiliar function to encode a markdown line, switching bold on and off at each marker
+fn markdown_line(line: &str, bold: &mut bool, code_table: &CodeTable) -> Result<Vec<u8>, Error> {
+    let mut target = Vec::new();
+    for (idx, segment) in line.split(BOLD_MARKER).enumerate() {
+        if idx > 0 {
+            *bold = !*bold;
+            target.append(&mut if *bold { Command::BoldOn } else { Command::BoldOff }.as_bytes());
+        }
+        target.append(&mut code_table.encode(segment)?);
+    }
+    Ok(target)
+}
+
+// Auxiliar function to end a line with an ellipsis, without exceeding the width
+fn ellipsize(line: &mut String, width: usize) {
+    let keep = line.chars().count().min(width.saturating_sub(3));
+    *line = line.chars().take(keep).collect::<String>() + "...";
+}
+
 // Auxiliar function to obtain three-row formatted string
-fn trio_row(mut row: (String, String, String), width: usize, max_left: usize, max_right: usize) -> String {
+fn trio_row(row: (String, String, String), width: usize, max_left: usize, max_right: usize) -> String {
+    let cells = trio_cells(row, width, max_left, max_right);
+    format!("{}{}{}\n", cells.0, cells.1, cells.2)
+}
+
+// Auxiliar function to obtain the three padded cells of a row
+fn trio_cells(mut row: (String, String, String), width: usize, max_left: usize, max_right: usize) -> (String, String, String) {
     if row.0.len() > max_left {
         row.0.replace_range((max_left-2).., "..");
     }
@@ -591,8 +1568,18 @@ fn trio_row(mut row: (String, String, String), width: usize, max_left: usize, ma
     row.2.truncate(max_right);
     row.1.truncate(width - max_left - max_right - 2);
 
-    format!("{:<3$}{:^4$}{:>5$}\n",
-        row.0, row.1, row.2, // Words
-        max_left, width - max_left - max_right, max_right // Lengths
+    (
+        format!("{:<1$}", row.0, max_left),
+        format!("{:^1$}", row.1, width - max_left - max_right),
+        format!("{:>1$}", row.2, max_right)
     )
+}
+
+// Auxiliar function to obtain a two-column row
+fn duo_row(row: &(String, String), width: usize, code_tables: &[Option<CodeTable>], default_code_table: &CodeTable) -> Result<Vec<u8>, Error> {
+    // The second column gets the room it needs first, and the first one gets truncated to what is left
+    let second: String = row.1.chars().take(width).collect();
+    let first: String = row.0.chars().take(width - second.chars().count()).collect();
+    let second = format!("{:>1$}", second, width.saturating_sub(first.chars().count()));
+    encode_cells(&[first, second], code_tables, default_code_table)
 }
\ No newline at end of file
@@ -0,0 +1,45 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+
+/// How the transparency of images gets handled
+///
+/// Thermal paper has no transparency, so each pixel of an image with an alpha channel either gets drawn or not. Antialiased edges of logos have semi-transparent pixels, and this mode decides what happens to them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AlphaMode {
+    /// Pixels with an alpha above the value are treated as opaque, and the rest as blank
+    Threshold(u8),
+    /// Each pixel gets blended onto a background of the given gray level before turning it into a dot, `255` being white paper
+    Flatten {
+        background: u8
+    }
+}
+
+impl Default for AlphaMode {
+    fn default() -> AlphaMode {
+        AlphaMode::Threshold(64)
+    }
+}
+
+impl AlphaMode {
+    /// Grayscale value of a pixel, or `None` if it should not be drawn
+    pub(crate) fn grayscale(&self, channels: &[u8]) -> Option<f64> {
+        let grayscale = 0.2126*(channels[0] as f64) + 0.7152*(channels[1] as f64) + 0.0722*(channels[2] as f64);
+        let alpha = match channels.get(3) {
+            Some(alpha) => *alpha,
+            // No alpha channel, so it is opaque
+            None => return Some(grayscale)
+        };
+        match self {
+            AlphaMode::Threshold(threshold) => if alpha > *threshold {
+                Some(grayscale)
+            } else {
+                None
+            },
+            AlphaMode::Flatten{background} => {
+                let opacity = (alpha as f64) / 255.0;
+                Some(grayscale * opacity + (*background as f64) * (1.0 - opacity))
+            }
+        }
+    }
+}
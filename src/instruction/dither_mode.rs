@@ -0,0 +1,72 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+
+/// Size of the Bayer matrix used for ordered dithering
+///
+/// Larger matrices give smoother gradients, while smaller ones keep sharper detail.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum BayerSize {
+    /// 2x2 matrix, 5 gray levels
+    Bayer2,
+    /// 4x4 matrix, 17 gray levels
+    Bayer4,
+    /// 8x8 matrix, 65 gray levels
+    Bayer8
+}
+
+impl BayerSize {
+    /// Side of the matrix
+    fn side(&self) -> usize {
+        match self {
+            BayerSize::Bayer2 => 2,
+            BayerSize::Bayer4 => 4,
+            BayerSize::Bayer8 => 8
+        }
+    }
+
+    /// Rank of the given position within the matrix, from 0 to side * side - 1
+    fn rank(&self, x: u32, y: u32) -> u32 {
+        let mut rank = 0;
+        let mut side = 1;
+        // Each level doubles the matrix, the finest position having the largest weight
+        while side < self.side() as u32 {
+            let (bx, by) = ((x / side) % 2, (y / side) % 2);
+            rank = 4 * rank + match (bx, by) {
+                (0, 0) => 0,
+                (1, 1) => 1,
+                (1, 0) => 2,
+                _ => 3
+            };
+            side *= 2;
+        }
+        rank
+    }
+}
+
+/// How images get turned into black and white dots
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum DitherMode {
+    /// Each pixel darker than a fixed threshold becomes a dot
+    #[default]
+    Threshold,
+    /// Ordered dithering, the threshold varies with the position according to a Bayer matrix
+    Ordered {
+        matrix: BayerSize
+    }
+}
+
+impl DitherMode {
+    /// Grayscale value under which the pixel at the given position becomes a dot
+    pub(crate) fn threshold(&self, x: u32, y: u32) -> f64 {
+        match self {
+            DitherMode::Threshold => 78.0,
+            DitherMode::Ordered{matrix} => {
+                let side = matrix.side() as u32;
+                let levels = (side * side) as f64;
+                let rank = matrix.rank(x % side, y % side) as f64;
+                255.0 * (rank + 0.5) / levels
+            }
+        }
+    }
+}
@@ -1,11 +1,9 @@
 extern crate serde;
 extern crate base64;
 extern crate image;
-extern crate log;
 
-use log::warn;
-use super::{Justification};
-use crate::{Error, command::{Command}};
+use super::{Justification, DitherMode, AlphaMode, ImageMode};
+use crate::{Error, Diagnostic, PrinterProfile, command::{Command}, diagnostic::report};
 use image::{DynamicImage, GenericImageView, Pixel};
 use serde::{Serialize, Deserialize, ser::Serializer, de::Deserializer};
 
@@ -20,10 +18,16 @@ pub struct EscposImage {
     source: String,
     /// Source image, usefull for scaling
     dynamic_image: DynamicImage,
-    ///
+    /// Widths for which the image has been cached
     cached_widths: HashSet<u16>,
     /// Cache that holds the picture scaled for specific widths
-    pub(crate) cache: HashMap<u16, Vec<u8>>
+    pub(crate) cache: HashMap<u16, Vec<u8>>,
+    /// How pixels become dots
+    dither_mode: DitherMode,
+    /// How transparent pixels get treated
+    alpha_mode: AlphaMode,
+    /// Density of the bit image
+    image_mode: ImageMode
 }
 
 impl EscposImage {
@@ -74,70 +78,262 @@ impl EscposImage {
             source,
             dynamic_image,
             cached_widths: HashSet::new(),
-            cache: HashMap::new()
+            cache: HashMap::new(),
+            dither_mode: DitherMode::default(),
+            alpha_mode: AlphaMode::default(),
+            image_mode: ImageMode::default()
+        })
+    }
+
+    /// Creates a new EscposImage that fits a box, keeping the aspect ratio
+    ///
+    /// The image gets scaled to be as large as possible within `max_width_dots` and `max_height_dots`, and the unused space is left transparent, so the resulting image always has the size of the box. Horizontally, the image is placed according to the justification, and vertically it gets centered. Useful for logos in label areas of a fixed size.
+    ///
+    /// The box needs at least a dot in each direction, otherwise an [EmptyBox](crate::Error::EmptyBox) error is returned.
+    ///
+    /// Keep in mind that images get scaled to the printer's width when printed, so the box matches dots one to one only when `max_width_dots` is the printer's width.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification};
+    /// use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+    ///
+    /// let black = |width, height| DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255])));
+    ///
+    /// // A wide image is limited by the width, leaving space above and below
+    /// let wide = EscposImage::new_fit_box(black(400, 100), 200, 200, Justification::Center)?;
+    /// assert_eq!((200, 200), (wide.width(), wide.height()));
+    /// assert_eq!(0, wide.dynamic_image().get_pixel(100, 10)[3]);
+    /// assert_eq!(255, wide.dynamic_image().get_pixel(100, 100)[3]);
+    ///
+    /// // A tall image is limited by the height, leaving space at the sides
+    /// let tall = EscposImage::new_fit_box(black(100, 400), 200, 200, Justification::Center)?;
+    /// assert_eq!((200, 200), (tall.width(), tall.height()));
+    /// assert_eq!(0, tall.dynamic_image().get_pixel(10, 100)[3]);
+    /// assert_eq!(255, tall.dynamic_image().get_pixel(100, 100)[3]);
+    ///
+    /// // An empty box can not hold the image
+    /// assert!(EscposImage::new_fit_box(black(100, 400), 0, 200, Justification::Center).is_err());
+    /// assert!(EscposImage::new_fit_box(black(100, 400), 200, 0, Justification::Center).is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn new_fit_box(dynamic_image: DynamicImage, max_width_dots: u16, max_height_dots: u16, justification: Justification) -> Result<EscposImage, Error> {
+        if max_width_dots == 0 || max_height_dots == 0 {
+            return Err(Error::EmptyBox{width: max_width_dots, height: max_height_dots});
+        }
+        let (im_width, im_height) = dynamic_image.dimensions();
+        let (box_width, box_height) = (max_width_dots as u32, max_height_dots as u32);
+
+        // The most limiting dimension sets the scale
+        let ratio = ((box_width as f64)/(im_width as f64)).min((box_height as f64)/(im_height as f64));
+        let sc_width = (((im_width as f64) * ratio).floor() as u32).clamp(1, box_width);
+        let sc_height = (((im_height as f64) * ratio).floor() as u32).clamp(1, box_height);
+
+        let x_offset = match justification {
+            Justification::Left => 0,
+            Justification::Center => (box_width - sc_width)/2,
+            Justification::Right => box_width - sc_width
+        };
+        let y_offset = (box_height - sc_height)/2;
+
+        let mut back = DynamicImage::new_rgba8(box_width, box_height);
+        image::imageops::overlay(
+            &mut back,
+            &image::imageops::resize(&dynamic_image, sc_width, sc_height, image::imageops::FilterType::Nearest),
+            x_offset, y_offset
+        );
+
+        let mut encoded = Vec::new();
+        back.write_to(&mut encoded, image::ImageFormat::Png).map_err(Error::ImageError)?;
+
+        Ok(EscposImage {
+            source: base64::encode(&encoded),
+            dynamic_image: back,
+            cached_widths: HashSet::new(),
+            cache: HashMap::new(),
+            dither_mode: DitherMode::default(),
+            alpha_mode: AlphaMode::default(),
+            image_mode: ImageMode::default()
+        })
+    }
+
+    /// Creates a copy of the image, resized to the given width in dots
+    ///
+    /// The aspect ratio is kept. Unlike the scaling done when printing, the resized image replaces the source, so it can be cached and serialized as such.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification};
+    /// let escpos_image = EscposImage::new(image::DynamicImage::new_rgba8(800, 400), 255, Justification::Left)?;
+    /// let thumbnail = escpos_image.scaled_to(200)?;
+    /// assert_eq!(200, thumbnail.width());
+    /// assert_eq!(100, thumbnail.height());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn scaled_to(&self, dots: u16) -> Result<EscposImage, Error> {
+        let (im_width, im_height) = self.dynamic_image.dimensions();
+        let height = (((dots as f64) * (im_height as f64) / (im_width as f64)).round() as u32).max(1);
+        let dynamic_image = self.dynamic_image.resize_exact(dots as u32, height, image::imageops::FilterType::Nearest);
+
+        let mut encoded = Vec::new();
+        dynamic_image.write_to(&mut encoded, image::ImageFormat::Png).map_err(Error::ImageError)?;
+
+        Ok(EscposImage {
+            source: base64::encode(&encoded),
+            dynamic_image,
+            cached_widths: HashSet::new(),
+            cache: HashMap::new(),
+            dither_mode: self.dither_mode,
+            alpha_mode: self.alpha_mode,
+            image_mode: self.image_mode
         })
     }
 
-    fn build_scaled(&self, printer_width: u16) -> Vec<u8> {
+    /// Sets how the pixels of the image become dots
+    ///
+    /// By default, each pixel darker than a fixed threshold becomes a dot, which works well for logos. Photos and gradients look better with [ordered dithering](DitherMode::Ordered). Cached widths get rebuilt with the new mode.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, DitherMode, BayerSize};
+    /// use image::{DynamicImage, Luma, GrayImage};
+    ///
+    /// // Horizontal gradient, from black to white
+    /// let gradient = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 24, |x, _| Luma([(x * 4) as u8])));
+    /// let escpos_image = EscposImage::new(gradient, 255, Justification::Left)?;
+    /// let bayer_2 = escpos_image.clone().with_dither_mode(DitherMode::Ordered{matrix: BayerSize::Bayer2});
+    /// let bayer_8 = escpos_image.clone().with_dither_mode(DitherMode::Ordered{matrix: BayerSize::Bayer8});
+    /// assert_ne!(bayer_2.feed(64), bayer_8.feed(64));
+    /// assert_ne!(escpos_image.feed(64), bayer_8.feed(64));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_dither_mode(mut self, dither_mode: DitherMode) -> EscposImage {
+        self.dither_mode = dither_mode;
+        for width in self.cached_widths.clone() {
+            self.cache_for(width);
+        }
+        self
+    }
+
+    /// Sets how the transparent pixels of the image get treated
+    ///
+    /// By default, pixels with an alpha of 64 or less are left blank and the rest are treated as opaque, which makes antialiased edges flip abruptly. A different [threshold](AlphaMode::Threshold) moves that cut, while [flattening](AlphaMode::Flatten) blends each pixel onto a background first, so semi-transparent pixels behave like the gray they would look like on paper. Cached widths get rebuilt with the new mode.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, AlphaMode};
+    /// use image::{DynamicImage, Rgba, RgbaImage};
+    ///
+    /// // Black, fading from transparent to opaque
+    /// let gradient = DynamicImage::ImageRgba8(RgbaImage::from_fn(256, 8, |x, _| Rgba([0, 0, 0, x as u8])));
+    /// let escpos_image = EscposImage::new(gradient, 255, Justification::Left)?;
+    /// // Number of columns with at least a dot
+    /// let dots = |escpos_image: &EscposImage| {
+    ///     let feed = escpos_image.feed(256);
+    ///     let start = feed.windows(3).position(|window| window == [0x1b, 0x2a, 0x01]).unwrap() + 5;
+    ///     feed[start..start + 256].iter().filter(|column| **column != 0).count()
+    /// };
+    ///
+    /// // Alpha values from 65 to 255 get drawn
+    /// assert_eq!(191, dots(&escpos_image));
+    /// assert_eq!(55, dots(&escpos_image.clone().with_alpha_mode(AlphaMode::Threshold(200))));
+    /// // On white paper, only the nearly opaque pixels are dark enough
+    /// assert_eq!(78, dots(&escpos_image.clone().with_alpha_mode(AlphaMode::Flatten{background: 255})));
+    /// // On a black background, everything is dark
+    /// assert_eq!(256, dots(&escpos_image.clone().with_alpha_mode(AlphaMode::Flatten{background: 0})));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_alpha_mode(mut self, alpha_mode: AlphaMode) -> EscposImage {
+        self.alpha_mode = alpha_mode;
+        for width in self.cached_widths.clone() {
+            self.cache_for(width);
+        }
+        self
+    }
+
+    /// Sets the density of the bit image
+    ///
+    /// By default, images get printed in [8 dot double density](ImageMode::EightDotDouble), where each dot is three times taller than wide. The 24 dot modes print square dots, for sharper images, if the printer supports them. Cached widths get rebuilt with the new mode.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, ImageMode};
+    ///
+    /// let escpos_image = EscposImage::new(image::DynamicImage::new_rgba8(64, 48), 255, Justification::Left)?
+    ///     .with_image_mode(ImageMode::TwentyFourDotDouble);
+    /// assert_eq!(ImageMode::TwentyFourDotDouble, escpos_image.image_mode());
+    /// // Bands of 24 dots, 3 bytes for each of the 64 columns
+    /// let feed = escpos_image.feed(64);
+    /// let start = feed.windows(5).position(|window| window == [0x1b, 0x2a, 0x21, 64, 0]).unwrap() + 5;
+    /// assert_eq!(b'\n', feed[start + 3 * 64]);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_image_mode(mut self, image_mode: ImageMode) -> EscposImage {
+        self.image_mode = image_mode;
+        for width in self.cached_widths.clone() {
+            self.cache_for(width);
+        }
+        self
+    }
+
+    /// Density of the bit image, see [with_image_mode](EscposImage::with_image_mode)
+    pub fn image_mode(&self) -> ImageMode {
+        self.image_mode
+    }
+
+    /// Width of the source image, in pixels
+    pub fn width(&self) -> u32 {
+        self.dynamic_image.width()
+    }
+
+    /// Height of the source image, in pixels
+    pub fn height(&self) -> u32 {
+        self.dynamic_image.height()
+    }
+
+    /// Source image, as it will be scaled for printing
+    pub fn dynamic_image(&self) -> &DynamicImage {
+        &self.dynamic_image
+    }
+
+    fn build_scaled(&self, printer_width: u16, image_mode: ImageMode) -> Vec<u8> {
         let mut feed = Vec::new();
         feed.extend_from_slice(&Command::NoLine.as_bytes());
         
-        let (im_width, im_height) = self.dynamic_image.dimensions();
-        // We redefine the aspect ratio
-        let aspect_ratio = (im_width as f64)/(im_height as f64);
-        
-        // Each row will contain the information of 8 rows from the picture
-        //const printer_width: usize = 384;
-        //const printer_width: usize = 576;
-        //let mut printer_rows: Vec<[u8; printer_width]> = Vec::new();
-        let mut printer_rows: Vec<Vec<u8>> = Vec::new();
-
-        // El *3 es por la baja densidad de impresión vertical (1 byte en lugar de 3)
-        let new_height = ((printer_width as f64)/(aspect_ratio*3.0)).floor() as u32;
+        let (columns, new_height) = self.scaled_size(printer_width, image_mode);
+        // Each row will contain the information of a band of rows from the picture, with a byte per 8 rows
+        let band_height = image_mode.band_height();
+        let bytes_per_column = (band_height / 8) as usize;
+        // The last band gets padded with blank rows, so the bottom of the image is kept
+        let mut printer_rows: Vec<Vec<u8>> = vec![vec![0; (columns as usize) * bytes_per_column]; new_height.div_ceil(band_height) as usize];
         
-        let b = image::imageops::resize(&self.dynamic_image, printer_width as u32, new_height, image::imageops::FilterType::Nearest);
+        let b = image::imageops::resize(&self.dynamic_image, columns, new_height, image::imageops::FilterType::Nearest);
 
         // We will turn the image into a grayscale boolean matrix
         for (y, pixel_row) in b.enumerate_rows() {
             // Here we iterate over each row of the image.
-            if y%8 == 0 {
-                printer_rows.push(vec![0; printer_width as usize]);
-            }
-            let row = printer_rows.get_mut((y/8) as usize).unwrap();
+            let row = printer_rows.get_mut((y/band_height) as usize).unwrap();
+            let band_y = y%band_height;
             // Here, we iterate horizontally this time
             for (x, y, pixel) in pixel_row {
                 let ps = pixel.channels();
                 // We get the color as a boolean
-                let mut color = if ps.len() == 3 || ps[3] > 64 {
-                    let grayscale = 0.2126*(ps[0] as f64) + 0.7152*(ps[1] as f64) + 0.0722*(ps[2] as f64);
-                    if grayscale < 78.0 {
-                        0x01
-                    } else {
-                        0x00
-                    }
-                } else {
-                    // It is transparent, so no color
-                    0x00
+                let mut color = match self.alpha_mode.grayscale(ps) {
+                    Some(grayscale) if grayscale < self.dither_mode.threshold(x, y) => 0x01,
+                    // Either too light, or transparent, so no color
+                    _other => 0x00
                 };
                 // We shift the boolean by 7 - y%8 positions in the register
-                color <<= 7 - y%8;
+                color <<= 7 - band_y%8;
                 // An or operation preserves the previous pixels in the rows
-                row[x as usize] |= color;
+                row[(x as usize) * bytes_per_column + (band_y / 8) as usize] |= color;
             }
         }
 
         // Finally, we push each row to the feed vector
-        for (_idx, printer_row) in printer_rows.iter().enumerate() {
+        for printer_row in printer_rows.iter() {
             // We first, declare a bitmap mode
             feed.extend_from_slice(&Command::Bitmap.as_bytes());
             // Now, we pass m
-            let m = 0x01;
-            feed.push(m);
-            // The formula on how many pixels we will do, is nL + nH * 256
-            feed.push((printer_width % 256) as u8); // nL
-            feed.push((printer_width / 256) as u8); // nH
-            // feed.push(0x80); // nL
-            // feed.push(0x01); // nH
+            feed.push(image_mode.m());
+            // The formula on how many columns we will do, is nL + nH * 256
+            feed.push((columns % 256) as u8); // nL
+            feed.push((columns / 256) as u8); // nH
             feed.extend_from_slice(printer_row);
             feed.push(b'\n'); // Line feed and print
         }
@@ -147,56 +343,267 @@ impl EscposImage {
         feed
     }
 
+    // Columns and rows of the image once scaled for the printer width and mode
+    fn scaled_size(&self, printer_width: u16, image_mode: ImageMode) -> (u32, u32) {
+        let (im_width, im_height) = self.dynamic_image.dimensions();
+        // We redefine the aspect ratio
+        let aspect_ratio = (im_width as f64)/(im_height as f64);
+        // Single density modes take every other dot
+        let columns = (printer_width as u32) / image_mode.dot_width();
+        // El *3 es por la baja densidad de impresión vertical (1 byte en lugar de 3), solo en los modos de 8 puntos
+        let rows = ((printer_width as f64)/(aspect_ratio*(image_mode.dot_height() as f64))).floor() as u32;
+        (columns, rows)
+    }
+
+    /// Builds the image for the given mode and width, failing if nothing would get printed
+    ///
+    /// Unlike [feed](EscposImage::feed), which can only log problems, this method returns an error when the image would end up with no columns or no rows at the requested width (for example, a very wide image scaled to a narrow printer). The row count gets padded with blank rows up to the band height of the mode (8 or 24 dots), so the bottom of the image is never cut off. The cache is used only if the mode is the image's own mode.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, ImageMode};
+    /// use image::{DynamicImage, Rgba, RgbaImage};
+    ///
+    /// // 50 rows, which leaves 2 rows for the third band of 24 dots
+    /// let black = DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 50, Rgba([0, 0, 0, 255])));
+    /// let escpos_image = EscposImage::new(black, 255, Justification::Left)?;
+    /// let feed = escpos_image.feed_checked(ImageMode::TwentyFourDotDouble, 64)?;
+    /// let bands: Vec<usize> = feed.windows(5).enumerate()
+    ///     .filter(|(_, window)| *window == [0x1b, 0x2a, 0x21, 64, 0])
+    ///     .map(|(idx, _)| idx + 5)
+    ///     .collect();
+    /// assert_eq!(3, bands.len());
+    /// // Every column of the last band has its two top dots printed, and the padding left blank
+    /// let last_band = &feed[bands[2]..bands[2] + 3 * 64];
+    /// assert!(last_band.chunks(3).all(|column| column == [0xc0, 0x00, 0x00]));
+    ///
+    /// // Too wide to keep a single row at 64 dots
+    /// let line = EscposImage::new(DynamicImage::new_rgba8(1000, 1), 255, Justification::Left)?;
+    /// assert!(line.feed_checked(ImageMode::TwentyFourDotDouble, 64).is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn feed_checked(&self, image_mode: ImageMode, width: u16) -> Result<Vec<u8>, Error> {
+        let (columns, rows) = self.scaled_size(width, image_mode);
+        if columns == 0 || rows == 0 {
+            return Err(Error::EmptyImage{width});
+        }
+        match self.cache.get(&width) {
+            Some(feed) if image_mode == self.image_mode => Ok(feed.clone()),
+            _other => Ok(self.build_scaled(width, image_mode))
+        }
+    }
+
+    /// Builds the image as raster graphics (`GS v 0`), in blocks of at most `max_rows` rows
+    ///
+    /// Raster graphics print square dots at the full density, so the image keeps its aspect ratio at `width` dots. Some printers drop rows when a single raster command exceeds their buffer, so tall images get split into several standalone commands, each one with at most `max_rows` rows (at least one). Values like 255, or the 1662 rows some models document, are common.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, validate_escpos};
+    ///
+    /// let escpos_image = EscposImage::new(image::DynamicImage::new_rgba8(64, 1000), 255, Justification::Left)?;
+    /// let feed = escpos_image.feed_raster(64, 255);
+    /// assert_eq!(Ok(()), validate_escpos(&feed));
+    ///
+    /// // Each block is 8 bytes wide, for 64 dots
+    /// let mut heights = Vec::new();
+    /// let mut idx = 0;
+    /// while idx < feed.len() {
+    ///     assert_eq!([0x1d, 0x76, 0x30, 0x00, 8, 0], feed[idx..idx + 6]);
+    ///     let rows = feed[idx + 6] as usize + 256 * feed[idx + 7] as usize;
+    ///     heights.push(rows);
+    ///     idx += 8 + 8 * rows;
+    /// }
+    /// assert_eq!(vec![255, 255, 255, 235], heights);
+    /// assert_eq!(1000, heights.iter().sum::<usize>());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn feed_raster(&self, width: u16, max_rows: u16) -> Vec<u8> {
+        let (im_width, im_height) = self.dynamic_image.dimensions();
+        let new_height = (((width as f64) * (im_height as f64) / (im_width as f64)).round() as u32).max(1);
+        let b = image::imageops::resize(&self.dynamic_image, width as u32, new_height, image::imageops::FilterType::Nearest);
+        let bytes_per_row = (width as usize).div_ceil(8);
+
+        // Each row of the image, 8 dots per byte with the leftmost one in the highest bit
+        let rows: Vec<Vec<u8>> = b.enumerate_rows().map(|(_, pixel_row)| {
+            let mut row = vec![0; bytes_per_row];
+            for (x, y, pixel) in pixel_row {
+                let dark = match self.alpha_mode.grayscale(pixel.channels()) {
+                    Some(grayscale) => grayscale < self.dither_mode.threshold(x, y),
+                    None => false
+                };
+                if dark {
+                    row[(x / 8) as usize] |= 0x80 >> (x % 8);
+                }
+            }
+            row
+        }).collect();
+
+        let mut feed = Vec::new();
+        for block in rows.chunks(max_rows.max(1) as usize) {
+            feed.extend_from_slice(&[0x1d, 0x76, 0x30, 0x00]);
+            feed.push((bytes_per_row % 256) as u8); // xL
+            feed.push((bytes_per_row / 256) as u8); // xH
+            feed.push((block.len() % 256) as u8); // yL
+            feed.push((block.len() / 256) as u8); // yH
+            for row in block {
+                feed.extend_from_slice(row);
+            }
+        }
+        feed
+    }
+
     /// Creates a cached image for the specified width
     ///
     /// Useful method to decrease the number of operations done per printing, by skipping the scaling step for a specific printer.
     pub fn cache_for(&mut self, width: u16) {
-        self.cache.insert(width, self.build_scaled(width));
+        self.cache.insert(width, self.build_scaled(width, self.image_mode));
         self.cached_widths.insert(width);
     }
 
+    /// Creates a cached image for the width of the given profile
+    ///
+    /// Same as [cache_for](EscposImage::cache_for), with the width taken from the profile of the target printer, so images can be prepared once without repeating the printer's width.
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, PrinterProfile, Justification};
+    /// use std::cell::RefCell;
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_width(576).build();
+    /// let mut escpos_image = EscposImage::new(image::DynamicImage::new_rgba8(8, 8), 255, Justification::Left)?;
+    /// escpos_image.cache_for_profile(&printer_profile);
+    ///
+    /// // The image does not get built again when printing
+    /// let reported = RefCell::new(Vec::new());
+    /// escpos_image.feed_with_handler(printer_profile.width(), Some(&|diagnostic| reported.borrow_mut().push(diagnostic)));
+    /// assert!(reported.into_inner().is_empty());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn cache_for_profile(&mut self, printer_profile: &PrinterProfile) {
+        self.cache_for(printer_profile.width);
+    }
+
     pub fn feed(&self, width: u16) -> Vec<u8> {
+        self.feed_with_handler(width, None)
+    }
+
+    /// Same as [feed](EscposImage::feed), but reporting diagnostics to the given handler instead of the log
+    ///
+    /// ```rust
+    /// use escpos_rs::{EscposImage, Justification, Diagnostic};
+    /// use std::cell::RefCell;
+    ///
+    /// let escpos_image = EscposImage::new(image::DynamicImage::new_rgba8(8, 8), 255, Justification::Left)?;
+    /// let reported = RefCell::new(Vec::new());
+    /// escpos_image.feed_with_handler(384, Some(&|diagnostic| reported.borrow_mut().push(diagnostic)));
+    /// assert_eq!(vec![Diagnostic::ImageBuiltOnTheFly{width: 384}], reported.into_inner());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn feed_with_handler(&self, width: u16, handler: Option<&dyn Fn(Diagnostic)>) -> Vec<u8> {
         if let Some(feed) = self.cache.get(&width) {
             feed.clone()
         } else {
             // We have to create the picture... might be costly
-            warn!("Building an image on the fly in non-mutable mode. Consider caching the width.");
-            self.build_scaled(width)
+            report(handler, Diagnostic::ImageBuiltOnTheFly{width});
+            self.build_scaled(width, self.image_mode)
+        }
+    }
+
+    /// Same as [feed_with_handler](EscposImage::feed_with_handler), for the width and image modes of a profile
+    ///
+    /// If the profile does not support the image's mode, the image gets built with the closest supported one.
+    pub(crate) fn feed_for_profile(&self, printer_profile: &PrinterProfile, handler: Option<&dyn Fn(Diagnostic)>) -> Result<Vec<u8>, Error> {
+        let image_mode = self.printing_mode(printer_profile)?;
+        if image_mode == self.image_mode {
+            Ok(self.feed_with_handler(printer_profile.width, handler))
+        } else {
+            report(handler, Diagnostic::ImageModeFallback{requested: self.image_mode, used: image_mode});
+            Ok(self.build_scaled(printer_profile.width, image_mode))
         }
     }
+
+    /// Mode used to print the image with the given profile
+    pub(crate) fn printing_mode(&self, printer_profile: &PrinterProfile) -> Result<ImageMode, Error> {
+        self.image_mode.closest_in(&printer_profile.supported_image_modes).ok_or(Error::NoImageModeFound)
+    }
 }
 
 // Manual implementation of serialization
 impl Serialize for EscposImage {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where S: Serializer {
-        let mut tup = serializer.serialize_tuple(2)?;
-        tup.serialize_element(&self.source)?;
+        let mut tup = serializer.serialize_tuple(5)?;
+        tup.serialize_element(&ImageSource(self.source.clone()))?;
         tup.serialize_element(&self.cached_widths)?;
+        tup.serialize_element(&self.dither_mode)?;
+        tup.serialize_element(&self.alpha_mode)?;
+        tup.serialize_element(&self.image_mode)?;
         tup.end()
     }
 }
 
+// Encoded image, as base64 text for human readable formats, and as raw bytes for binary ones
+struct ImageSource(String);
+
+impl Serialize for ImageSource {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.0)
+        } else {
+            let content = base64::decode(&self.0).map_err(|_| serde::ser::Error::custom("source is not a valid base64 sequence"))?;
+            serializer.serialize_bytes(&content)
+        }
+    }
+}
+
+struct ImageSourceVisitor;
+
+impl<'de> serde::de::Visitor<'de> for ImageSourceVisitor {
+    type Value = ImageSource;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a base64 encoded image, or the raw bytes of an image")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: serde::de::Error {
+        match base64::decode(value) {
+            Ok(_) => Ok(ImageSource(value.to_string())),
+            Err(_) => Err(E::custom("string is not a valid base64 sequence"))
+        }
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> where E: serde::de::Error {
+        Ok(ImageSource(base64::encode(value)))
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageSource {
+    fn deserialize<D>(deserializer: D) -> Result<ImageSource, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_any(ImageSourceVisitor)
+    }
+}
+
 struct EscposImageVisitor;
 
 impl<'de> serde::de::Visitor<'de> for EscposImageVisitor {
     type Value = EscposImage;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a tuple containing as first element a base64 encoded image, as second a list of cached widths")
+        formatter.write_str("a tuple containing as first element an encoded image, as second a list of cached widths, and optionally as third the dither mode, as fourth the alpha mode and as fifth the image mode")
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
-        let value: Option<&[u8]> = seq.next_element()?;
+        let value: Option<ImageSource> = seq.next_element()?;
         let value = value.ok_or_else(|| serde::de::Error::custom("first element of tuple missing"))?;
-        let content = match base64::decode(value) {
-            Ok(v) => v,
-            Err(_) => return Err(serde::de::Error::custom("string is not a valid base64 sequence"))
-        };
+        let content = base64::decode(&value.0).map_err(|_| serde::de::Error::custom("string is not a valid base64 sequence"))?;
         let dynamic_image = image::load_from_memory(&content).map_err(|_| serde::de::Error::custom("first element of tuple not an image"))?;
         // We will serialize it already
         let mut escpos_image = EscposImage::new(dynamic_image, 255, Justification::Left).map_err(|e| serde::de::Error::custom(format!("failed to create the image, {}", e)))?;
         let cached_widths: HashSet<u16> = seq.next_element()?.ok_or_else(|| serde::de::Error::custom("second element of tuple missing"))?;
+        // Images serialized before dither modes existed carry no third element
+        escpos_image.dither_mode = seq.next_element()?.unwrap_or_default();
+        escpos_image.alpha_mode = seq.next_element()?.unwrap_or_default();
+        escpos_image.image_mode = seq.next_element()?.unwrap_or_default();
 
         for width in cached_widths {
             escpos_image.cache_for(width);
@@ -210,6 +617,6 @@ impl<'de> serde::de::Visitor<'de> for EscposImageVisitor {
 impl<'de> Deserialize<'de> for EscposImage {
     fn deserialize<D>(deserializer: D) -> Result<EscposImage, D::Error>
     where D: Deserializer<'de> {
-        deserializer.deserialize_seq(EscposImageVisitor)
+        deserializer.deserialize_tuple(5, EscposImageVisitor)
     }
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+extern crate serde;
+
+use serde::{Serialize, Deserialize};
+
+/// Density of the bit images sent to the printer, as the `m` parameter of `ESC *`
+///
+/// The 8 dot modes send bands of 8 dots, printed three times taller, while the 24 dot modes send bands of 24 dots at the full vertical density. Single density modes use every other horizontal dot. Not every printer understands all of them, see [with_image_modes](crate::PrinterProfileBuilder::with_image_modes).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ImageMode {
+    /// 8 dots per band, single horizontal density (`m = 0`)
+    EightDotSingle,
+    /// 8 dots per band, double horizontal density (`m = 1`)
+    #[default]
+    EightDotDouble,
+    /// 24 dots per band, single horizontal density (`m = 32`)
+    TwentyFourDotSingle,
+    /// 24 dots per band, double horizontal density (`m = 33`)
+    TwentyFourDotDouble
+}
+
+impl ImageMode {
+    /// Finds the mode closest to this one among the supported ones
+    ///
+    /// The mode itself is preferred, then the one with the same band height and the other density, then the one with the same density and the other band height, and last the remaining one. Gives back `None` if no mode is supported.
+    ///
+    /// ```rust
+    /// use escpos_rs::ImageMode;
+    /// let eight_dot_only = vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble];
+    /// assert_eq!(Some(ImageMode::EightDotDouble), ImageMode::TwentyFourDotDouble.closest_in(&eight_dot_only));
+    /// assert_eq!(Some(ImageMode::EightDotSingle), ImageMode::TwentyFourDotSingle.closest_in(&eight_dot_only));
+    /// assert_eq!(Some(ImageMode::EightDotSingle), ImageMode::EightDotSingle.closest_in(&eight_dot_only));
+    /// assert_eq!(None, ImageMode::EightDotDouble.closest_in(&[]));
+    /// ```
+    pub fn closest_in(&self, supported: &[ImageMode]) -> Option<ImageMode> {
+        vec![*self, self.other_density(), self.other_band(), self.other_density().other_band()].into_iter().find(|mode| supported.contains(mode))
+    }
+
+    /// Value of the `m` parameter of `ESC *`
+    pub(crate) fn m(&self) -> u8 {
+        match self {
+            ImageMode::EightDotSingle => 0,
+            ImageMode::EightDotDouble => 1,
+            ImageMode::TwentyFourDotSingle => 32,
+            ImageMode::TwentyFourDotDouble => 33
+        }
+    }
+
+    /// Dots in each band of the image
+    pub(crate) fn band_height(&self) -> u32 {
+        match self {
+            ImageMode::EightDotSingle | ImageMode::EightDotDouble => 8,
+            ImageMode::TwentyFourDotSingle | ImageMode::TwentyFourDotDouble => 24
+        }
+    }
+
+    /// Printer dots covered by each column of the image
+    pub(crate) fn dot_width(&self) -> u32 {
+        match self {
+            ImageMode::EightDotSingle | ImageMode::TwentyFourDotSingle => 2,
+            ImageMode::EightDotDouble | ImageMode::TwentyFourDotDouble => 1
+        }
+    }
+
+    /// Printer dots covered by each row of the image
+    pub(crate) fn dot_height(&self) -> u32 {
+        match self {
+            ImageMode::EightDotSingle | ImageMode::EightDotDouble => 3,
+            ImageMode::TwentyFourDotSingle | ImageMode::TwentyFourDotDouble => 1
+        }
+    }
+
+    // Same band height, with the other density
+    fn other_density(&self) -> ImageMode {
+        match self {
+            ImageMode::EightDotSingle => ImageMode::EightDotDouble,
+            ImageMode::EightDotDouble => ImageMode::EightDotSingle,
+            ImageMode::TwentyFourDotSingle => ImageMode::TwentyFourDotDouble,
+            ImageMode::TwentyFourDotDouble => ImageMode::TwentyFourDotSingle
+        }
+    }
+
+    // Same density, with the other band height
+    fn other_band(&self) -> ImageMode {
+        match self {
+            ImageMode::EightDotSingle => ImageMode::TwentyFourDotSingle,
+            ImageMode::EightDotDouble => ImageMode::TwentyFourDotDouble,
+            ImageMode::TwentyFourDotSingle => ImageMode::EightDotSingle,
+            ImageMode::TwentyFourDotDouble => ImageMode::EightDotDouble
+        }
+    }
+}
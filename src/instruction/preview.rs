@@ -0,0 +1,291 @@
+extern crate image;
+
+use image::{DynamicImage, GrayImage, Luma};
+use qrcode::{QrCode, Color};
+use crate::{PrinterProfile, Justification, command::Font};
+use super::bitmap_font as font;
+
+/// Height, in dots, of a bit image line
+const BITMAP_LINE_HEIGHT: u32 = 24;
+
+/// Canvas that mimics the paper of the printer
+struct Paper {
+    /// Width of the paper, in dots
+    width: u32,
+    /// Printed rows, true meaning a black dot
+    rows: Vec<Vec<bool>>,
+    /// Horizontal position of the head
+    x: u32,
+    /// Vertical position of the current line
+    y: u32,
+    /// Height of the tallest element in the current line
+    line_height: u32,
+    /// Line spacing, if different from the default one
+    line_spacing: Option<u32>,
+    /// Width of a character cell for the selected font
+    cell_width: u32,
+    /// Justification for qr codes
+    justification: Justification,
+    /// Size, in dots, of each qr code module
+    qr_module_size: u32,
+    /// Data stored for the next qr code
+    qr_data: Vec<u8>
+}
+
+impl Paper {
+    fn new(width: u32, cell_width: u32) -> Paper {
+        Paper {
+            width,
+            rows: Vec::new(),
+            x: 0,
+            y: 0,
+            line_height: 0,
+            line_spacing: None,
+            cell_width,
+            justification: Justification::Left,
+            qr_module_size: 3,
+            qr_data: Vec::new()
+        }
+    }
+
+    /// Characters are twice as tall as they are wide
+    fn cell_height(&self) -> u32 {
+        self.cell_width * 2
+    }
+
+    fn set(&mut self, x: u32, y: u32) {
+        if x >= self.width {
+            return;
+        }
+        while self.rows.len() <= y as usize {
+            self.rows.push(vec![false; self.width as usize]);
+        }
+        self.rows[y as usize][x as usize] = true;
+    }
+
+    fn line_feed(&mut self) {
+        let advance = self.line_height.max(self.line_spacing.unwrap_or_else(|| self.cell_height()));
+        self.y += advance;
+        self.x = 0;
+        self.line_height = 0;
+        // The paper grows even with empty lines
+        while self.rows.len() < self.y as usize {
+            self.rows.push(vec![false; self.width as usize]);
+        }
+    }
+
+    fn character(&mut self, byte: u8) {
+        if self.x + self.cell_width > self.width {
+            // The printer breaks the line by itself
+            self.line_feed();
+        }
+        // Characters outside of ascii are shown as question marks
+        let glyph = font::glyph(byte as char);
+        let (cell_width, cell_height) = (self.cell_width, self.cell_height());
+        for dy in 0..cell_height {
+            let row = glyph[(dy * font::GLYPH_HEIGHT / cell_height) as usize];
+            for dx in 0..cell_width {
+                if row & (0x80 >> (dx * font::GLYPH_WIDTH / cell_width)) != 0 {
+                    self.set(self.x + dx, self.y + dy);
+                }
+            }
+        }
+        self.x += cell_width;
+        self.line_height = self.line_height.max(cell_height);
+    }
+
+    /// Draws a bit image column, with the given size per dot
+    fn bit_image_column(&mut self, column: &[u8], dot_size: (u32, u32)) {
+        for (idx, byte) in column.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0x80 >> bit) != 0 {
+                    let y = self.y + ((idx as u32) * 8 + bit) * dot_size.1;
+                    for dx in 0..dot_size.0 {
+                        for dy in 0..dot_size.1 {
+                            self.set(self.x + dx, y + dy);
+                        }
+                    }
+                }
+            }
+        }
+        self.x += dot_size.0;
+        self.line_height = self.line_height.max(BITMAP_LINE_HEIGHT);
+    }
+
+    /// Draws the stored qr code, on its own line
+    fn qr_code(&mut self) {
+        let code = match QrCode::new(&self.qr_data) {
+            Ok(code) => code,
+            Err(_) => return
+        };
+        if self.x != 0 {
+            self.line_feed();
+        }
+        let modules = code.width() as u32;
+        let size = modules * self.qr_module_size;
+        let x_offset = match self.justification {
+            Justification::Left => 0,
+            Justification::Center => self.width.saturating_sub(size) / 2,
+            Justification::Right => self.width.saturating_sub(size)
+        };
+        for (idx, color) in code.to_colors().iter().enumerate() {
+            if *color == Color::Dark {
+                let (mx, my) = ((idx as u32) % modules, (idx as u32) / modules);
+                for dx in 0..self.qr_module_size {
+                    for dy in 0..self.qr_module_size {
+                        self.set(x_offset + mx * self.qr_module_size + dx, self.y + my * self.qr_module_size + dy);
+                    }
+                }
+            }
+        }
+        self.y += size;
+        while self.rows.len() < self.y as usize {
+            self.rows.push(vec![false; self.width as usize]);
+        }
+    }
+
+    /// Marks a cut with a dashed line
+    fn cut(&mut self) {
+        if self.x != 0 {
+            self.line_feed();
+        }
+        for x in (0..self.width).filter(|x| (x / 4) % 2 == 0) {
+            self.set(x, self.y);
+        }
+        self.y += 1;
+    }
+
+    fn into_image(self) -> DynamicImage {
+        let height = (self.rows.len() as u32).max(self.y).max(1);
+        let mut image = GrayImage::from_pixel(self.width, height, Luma([255u8]));
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, dot) in row.iter().enumerate() {
+                if *dot {
+                    image.put_pixel(x as u32, y as u32, Luma([0u8]));
+                }
+            }
+        }
+        DynamicImage::ImageLuma8(image)
+    }
+}
+
+/// Rasterizes a sequence of esc/pos bytes, as produced by the instructions
+///
+/// Only the commands emitted by this crate are understood, unknown commands are skipped.
+pub(crate) fn render(bytes: &[u8], printer_profile: &PrinterProfile) -> DynamicImage {
+    let width = printer_profile.width as u32;
+    let cell_width_for = |font: &Font| {
+        printer_profile.columns_per_font.get(font)
+            .map(|columns| (width / (*columns as u32).max(1)).max(1))
+            .unwrap_or(12)
+    };
+    let mut paper = Paper::new(width, cell_width_for(&Font::FontA));
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\n' => {
+                paper.line_feed();
+                idx += 1;
+            },
+            0x1b => {
+                match bytes.get(idx + 1) {
+                    // Font selection
+                    Some(0x4d) => {
+                        let font = match bytes.get(idx + 2) {
+                            Some(0x01) => Font::FontB,
+                            Some(0x02) => Font::FontC,
+                            Some(0x03) => Font::FontD,
+                            Some(0x04) => Font::FontE,
+                            _ => Font::FontA
+                        };
+                        paper.cell_width = cell_width_for(&font);
+                        idx += 3;
+                    },
+                    // Bit image
+                    Some(0x2a) => {
+                        let m = bytes.get(idx + 2).copied().unwrap_or(0);
+                        let columns = bytes.get(idx + 3).copied().unwrap_or(0) as usize + 256 * bytes.get(idx + 4).copied().unwrap_or(0) as usize;
+                        let (bytes_per_column, dot_size) = match m {
+                            0 => (1, (2, 3)),
+                            1 => (1, (1, 3)),
+                            32 => (3, (2, 1)),
+                            _ => (3, (1, 1))
+                        };
+                        idx += 5;
+                        for _ in 0..columns {
+                            let end = (idx + bytes_per_column).min(bytes.len());
+                            paper.bit_image_column(&bytes[idx..end], dot_size);
+                            idx = end;
+                        }
+                    },
+                    // Line spacing
+                    Some(0x33) => {
+                        paper.line_spacing = bytes.get(idx + 2).map(|n| *n as u32);
+                        idx += 3;
+                    },
+                    Some(0x32) => {
+                        paper.line_spacing = None;
+                        idx += 2;
+                    },
+                    // Justification
+                    Some(0x61) => {
+                        paper.justification = match bytes.get(idx + 2) {
+                            Some(0x01) | Some(0x31) => Justification::Center,
+                            Some(0x02) | Some(0x32) => Justification::Right,
+                            _ => Justification::Left
+                        };
+                        idx += 3;
+                    },
+                    // Print and feed lines
+                    Some(0x64) => {
+                        for _ in 0..bytes.get(idx + 2).copied().unwrap_or(0) {
+                            paper.line_feed();
+                        }
+                        idx += 3;
+                    },
+                    // Commands with a single argument
+                    Some(_) => idx += 3,
+                    None => idx += 1
+                }
+            },
+            0x1d => {
+                match bytes.get(idx + 1) {
+                    // Paper cut
+                    Some(0x56) => {
+                        paper.cut();
+                        idx += if matches!(bytes.get(idx + 2), Some(0x41) | Some(0x42) | Some(0x61) | Some(0x62)) { 4 } else { 3 };
+                    },
+                    // Reset
+                    Some(0x40) => idx += 2,
+                    // Functions with a length, like qr codes
+                    Some(0x28) => {
+                        let length = bytes.get(idx + 3).copied().unwrap_or(0) as usize + 256 * bytes.get(idx + 4).copied().unwrap_or(0) as usize;
+                        let start = (idx + 5).min(bytes.len());
+                        let end = (start + length).min(bytes.len());
+                        let parameters = &bytes[start..end];
+                        if bytes.get(idx + 2) == Some(&0x6b) {
+                            match parameters {
+                                [0x31, 0x43, size, ..] => paper.qr_module_size = *size as u32,
+                                [0x31, 0x50, 0x30, data @ ..] => paper.qr_data = data.to_vec(),
+                                [0x31, 0x51, ..] => paper.qr_code(),
+                                _ => ()
+                            }
+                        }
+                        idx = end;
+                    },
+                    Some(_) => idx += 3,
+                    None => idx += 1
+                }
+            },
+            // Other control characters are ignored
+            byte if byte < 0x20 => idx += 1,
+            byte => {
+                paper.character(byte);
+                idx += 1;
+            }
+        }
+    }
+
+    paper.into_image()
+}
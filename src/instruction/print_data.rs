@@ -1,4 +1,12 @@
 use std::collections::HashMap;
+use super::EscposImage;
+#[cfg(feature = "json")]
+use crate::Error;
+
+/// Rows of a table with three columns
+type TrioRows = Vec<(String, String, String)>;
+/// Rows of a table with four columns
+type QuadRows = Vec<(String, String, String, String)>;
 
 /// Contains custom information for each print
 ///
@@ -6,9 +14,11 @@ use std::collections::HashMap;
 pub struct PrintData {
     pub(crate) replacements: HashMap<String, String>,
     pub(crate) duo_tables: Option<HashMap<String, Vec<(String, String)>>>,
-    pub(crate) trio_tables: Option<HashMap<String, Vec<(String, String, String)>>>,
-    pub(crate) quad_tables: Option<HashMap<String, Vec<(String, String, String, String)>>>,
-    pub(crate) qr_contents: Option<HashMap<String, String>>
+    pub(crate) trio_tables: Option<HashMap<String, TrioRows>>,
+    pub(crate) quad_tables: Option<HashMap<String, QuadRows>>,
+    pub(crate) qr_contents: Option<HashMap<String, String>>,
+    pub(crate) flags: Option<HashMap<String, bool>>,
+    pub(crate) images: Option<HashMap<String, EscposImage>>
 }
 
 impl PrintData {
@@ -16,27 +26,176 @@ impl PrintData {
     pub fn builder() -> PrintDataBuilder {
         PrintDataBuilder::new()
     }
+
+    /// Combines two print data structures
+    ///
+    /// The result contains everything from both, and when both have the same entry (a replacement, table, qr code, flag or image with the same name), the one from `other` wins. Useful to keep the data shared by many prints apart from the data of each print.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, Justification, command::Font};
+    ///
+    /// let common = PrintData::builder().replacement("%store%", "Coffee shop").replacement("%item%", "-").build();
+    /// let item = PrintData::builder().replacement("%item%", "Espresso").build();
+    /// let print_data = common.merge(&item);
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::text("%store%: %item%", Font::FontA, Justification::Left, Some(vec!["%store%".into(), "%item%".into()].into_iter().collect()));
+    /// let bytes = instruction.to_vec(&printer_profile, Some(&print_data))?;
+    /// assert!(bytes.ends_with(b"Coffee shop: Espresso\n"));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn merge(&self, other: &PrintData) -> PrintData {
+        // Auxiliar function to combine two optional maps
+        fn merge_maps<T: Clone>(first: &Option<HashMap<String, T>>, second: &Option<HashMap<String, T>>) -> Option<HashMap<String, T>> {
+            match (first, second) {
+                (None, None) => None,
+                (first, second) => {
+                    let mut merged = first.clone().unwrap_or_default();
+                    merged.extend(second.iter().flatten().map(|(key, value)| (key.clone(), value.clone())));
+                    Some(merged)
+                }
+            }
+        }
+
+        let mut replacements = self.replacements.clone();
+        replacements.extend(other.replacements.iter().map(|(key, value)| (key.clone(), value.clone())));
+        PrintData {
+            replacements,
+            duo_tables: merge_maps(&self.duo_tables, &other.duo_tables),
+            trio_tables: merge_maps(&self.trio_tables, &other.trio_tables),
+            quad_tables: merge_maps(&self.quad_tables, &other.quad_tables),
+            qr_contents: merge_maps(&self.qr_contents, &other.qr_contents),
+            flags: merge_maps(&self.flags, &other.flags),
+            images: merge_maps(&self.images, &other.images)
+        }
+    }
+
+    /// Constructs the print data from a json value
+    ///
+    /// Useful when the data is only known at runtime, like in a web service. Requires the `json` feature. The value must be an object, with any of the following keys:
+    ///
+    /// * `"replacements"`: object mapping each target to its replacement. Numbers and booleans get converted to text.
+    /// * `"tables"`: object mapping each table name to an array of rows, each row being an array of 2, 3 or 4 strings. The number of cells decides if it is a duo, trio or quad table, and all the rows of a table must have the same number of cells.
+    /// * `"qr_codes"`: object mapping each qr code name to its content.
+    /// * `"flags"`: object mapping each flag name to a boolean.
+    ///
+    /// Anything else results in an [InvalidPrintData](crate::Error::InvalidPrintData) error.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrintData, PrinterProfile, Justification, command::Font};
+    ///
+    /// let value = serde_json::json!({
+    ///     "replacements": {"%name%": "Carlos", "%items%": 2},
+    ///     "tables": {"products": [["Milk", "5.00"], ["Bread", "2.50"]]}
+    /// });
+    /// let print_data = PrintData::from_value(value)?;
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let instruction = Instruction::text("%name%, %items% items", Font::FontA, Justification::Left, Some(vec!["%name%".into(), "%items%".into()].into_iter().collect()))
+    ///     + Instruction::duo_table("products", ("Product", "Price"), Font::FontA);
+    /// let expected = PrintData::builder()
+    ///     .replacement("%name%", "Carlos")
+    ///     .replacement("%items%", "2")
+    ///     .add_duo_table("products", vec![("Milk".into(), "5.00".into()), ("Bread".into(), "2.50".into())])
+    ///     .build();
+    /// assert_eq!(instruction.to_vec(&printer_profile, Some(&expected))?, instruction.to_vec(&printer_profile, Some(&print_data))?);
+    ///
+    /// assert!(PrintData::from_value(serde_json::json!({"tables": {"products": [["Milk"]]}})).is_err());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_value(value: serde_json::Value) -> Result<PrintData, Error> {
+        use serde_json::Value;
+
+        // Auxiliar function to get the entries of an object
+        fn object(value: Value, what: &str) -> Result<serde_json::Map<String, Value>, Error> {
+            match value {
+                Value::Object(map) => Ok(map),
+                _other => Err(Error::InvalidPrintData(format!("{} must be an object", what)))
+            }
+        }
+
+        // Auxiliar function to turn plain values into text
+        fn text(value: Value, what: &str) -> Result<String, Error> {
+            match value {
+                Value::String(content) => Ok(content),
+                Value::Number(number) => Ok(number.to_string()),
+                Value::Bool(flag) => Ok(flag.to_string()),
+                _other => Err(Error::InvalidPrintData(format!("{} must be a string", what)))
+            }
+        }
+
+        let mut builder = PrintData::builder();
+        for (key, value) in object(value, "the print data")? {
+            match key.as_str() {
+                "replacements" => for (target, replacement) in object(value, "replacements")? {
+                    let replacement = text(replacement, &format!("replacement \"{}\"", target))?;
+                    builder = builder.replacement(target, replacement);
+                },
+                "qr_codes" => for (name, content) in object(value, "qr_codes")? {
+                    let content = text(content, &format!("qr code \"{}\"", name))?;
+                    builder = builder.add_qr_code(name, content);
+                },
+                "flags" => for (name, flag) in object(value, "flags")? {
+                    match flag {
+                        Value::Bool(flag) => builder = builder.flag(name, flag),
+                        _other => return Err(Error::InvalidPrintData(format!("flag \"{}\" must be a boolean", name)))
+                    }
+                },
+                "tables" => for (name, rows) in object(value, "tables")? {
+                    let rows = match rows {
+                        Value::Array(rows) => rows,
+                        _other => return Err(Error::InvalidPrintData(format!("table \"{}\" must be an array", name)))
+                    };
+                    let mut cells = Vec::new();
+                    for row in rows {
+                        let row = match row {
+                            Value::Array(row) => row,
+                            _other => return Err(Error::InvalidPrintData(format!("rows of table \"{}\" must be arrays", name)))
+                        };
+                        let row = row.into_iter().map(|cell| text(cell, &format!("cells of table \"{}\"", name))).collect::<Result<Vec<_>, _>>()?;
+                        cells.push(row);
+                    }
+                    let columns = cells.first().map(|row| row.len()).unwrap_or(2);
+                    if cells.iter().any(|row| row.len() != columns) {
+                        return Err(Error::InvalidPrintData(format!("rows of table \"{}\" have different lengths", name)));
+                    }
+                    builder = match columns {
+                        2 => builder.add_duo_table(name, cells.into_iter().map(|mut row| {
+                            let second = row.pop().unwrap();
+                            (row.pop().unwrap(), second)
+                        }).collect()),
+                        3 => builder.add_trio_table(name, cells.into_iter().map(|mut row| {
+                            let third = row.pop().unwrap();
+                            let second = row.pop().unwrap();
+                            (row.pop().unwrap(), second, third)
+                        }).collect()),
+                        4 => builder.add_quad_table(name, cells.into_iter().map(|mut row| {
+                            let fourth = row.pop().unwrap();
+                            let third = row.pop().unwrap();
+                            let second = row.pop().unwrap();
+                            (row.pop().unwrap(), second, third, fourth)
+                        }).collect()),
+                        _other => return Err(Error::InvalidPrintData(format!("rows of table \"{}\" must have 2, 3 or 4 cells", name)))
+                    };
+                },
+                other => return Err(Error::InvalidPrintData(format!("unknown key \"{}\"", other)))
+            }
+        }
+        Ok(builder.build())
+    }
 }
 
 /// Helps build a valid [PrintData](self::PrintData)
+#[derive(Default)]
 pub struct PrintDataBuilder {
     replacements: HashMap<String, String>,
     duo_tables: Option<HashMap<String, Vec<(String, String)>>>,
-    trio_tables: Option<HashMap<String, Vec<(String, String, String)>>>,
-    quad_tables: Option<HashMap<String, Vec<(String, String, String, String)>>>,
-    qr_contents: Option<HashMap<String, String>>
-}
-
-impl Default for PrintDataBuilder {
-    fn default() -> Self {
-        PrintDataBuilder {
-            replacements: HashMap::new(),
-            duo_tables: None,
-            trio_tables: None,
-            quad_tables: None,
-            qr_contents: None
-        }
-    }
+    trio_tables: Option<HashMap<String, TrioRows>>,
+    quad_tables: Option<HashMap<String, QuadRows>>,
+    qr_contents: Option<HashMap<String, String>>,
+    flags: Option<HashMap<String, bool>>,
+    images: Option<HashMap<String, EscposImage>>
 }
 
 impl PrintDataBuilder {
@@ -99,13 +258,44 @@ impl PrintDataBuilder {
         self
     }
 
+    /// Sets the value of a flag
+    ///
+    /// Flags decide which branch of a [conditional](crate::Instruction::conditional) instruction gets printed.
+    ///
+    /// ```rust
+    /// # use escpos_rs::PrintDataBuilder;
+    /// let print_data = PrintDataBuilder::new()
+    ///     .flag("discount", true)
+    ///     .build();
+    /// ```
+    pub fn flag<A: Into<String>>(mut self, name: A, value: bool) -> Self {
+        if let Some(flags) = &mut self.flags {
+            flags.insert(name.into(), value);
+        } else {
+            self.flags = Some(vec![(name.into(), value)].into_iter().collect());
+        }
+        self
+    }
+
+    /// Adds an image, for an [image reference](crate::Instruction::image_ref)
+    pub fn add_image<A: Into<String>>(mut self, name: A, image: EscposImage) -> Self {
+        if let Some(images) = &mut self.images {
+            images.insert(name.into(), image);
+        } else {
+            self.images = Some(vec![(name.into(), image)].into_iter().collect());
+        }
+        self
+    }
+
     pub fn build(self) -> PrintData {
         PrintData {
             replacements: self.replacements,
             duo_tables: self.duo_tables,
             trio_tables: self.trio_tables,
             quad_tables: self.quad_tables,
-            qr_contents: self.qr_contents
+            qr_contents: self.qr_contents,
+            flags: self.flags,
+            images: self.images
         }
     }
 }
\ No newline at end of file
@@ -3,6 +3,7 @@
 //! Not ready for production (yet, but soon!).
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "std")] {
 //! use escpos_rs::{Printer, PrinterProfile};
 //!
 //! // We create a usb contest for the printer
@@ -20,6 +21,7 @@
 //!     Ok(_) => (),
 //!     Err(e) => println!("Error: {}", e)
 //! }
+//! # }
 //! ```
 //! 
 //! See the [Printer](crate::Printer) structure to see the rest of the implemented functions for interacting with the thermal printer (raw printing, images, etc.).
@@ -37,6 +39,7 @@
 //! Because of the usual applications for thermal printers, the [Instruction](crate::Instruction) structure has been implemented, which allows you to define a sort of __template__, that you can use to print multiple documents with __certain__ data customized for each print.
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "std")] {
 //! use escpos_rs::{
 //!     Printer, PrintData, PrinterProfile,
 //!     Instruction, Justification, command::Font
@@ -81,19 +84,78 @@
 //!     Ok(_) => (), // "Hello, John!" should've been printed.
 //!     Err(e) => println!("Error: {}", e)
 //! }
+//! # }
 //! ```
 //!
 //! This structure implements both Serialize, and Deserialize from [serde](https://docs.rs/serde), so it is possible to store these instructions to recover them from memory. You can serialize to a json, as pictures are encoded to base64 first to be utf-8 compatible.
+//!
+//! ### Bytes without a printer
+//!
+//! Instructions can be turned into the bytes a printer expects, without a connection, which is handy to send them through another channel (a serial port, for example). A [Transport](crate::Transport) does the same for the whole [Printer](crate::Printer) api.
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! use escpos_rs::{PrinterProfile, Instruction, Justification, command::Font};
+//!
+//! let printer_profile = PrinterProfile::terminal_builder().build();
+//! let instruction = Instruction::text("Hello", Font::FontA, Justification::Left, None);
+//! let bytes = instruction.to_vec(&printer_profile, None)?;
+//! assert!(bytes.ends_with(b"Hello\n"));
+//! # }
+//! # Ok::<(), escpos_rs::Error>(())
+//! ```
+//!
+//! ### Without the standard library
+//!
+//! The raw [commands](crate::command), the code table encoding and the [Formatter](crate::Formatter) only need an allocator. Without the default `std` feature, and with the `alloc` one, the crate is `no_std` and keeps just those, which is enough to build the bytes for a printer attached to a microcontroller.
+//!
+//! ```toml
+//! escpos-rs = {version = "0.3", default-features = false, features = ["alloc"]}
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-pub use printer::{Printer, PrinterProfile, PrinterProfileBuilder, PrinterModel, PrinterConnectionData};
-pub use instruction::{Instruction, Justification, PrintData, PrintDataBuilder, EscposImage};
+#[cfg(feature = "std")]
+pub use printer::{Printer, PrinterProfile, PrinterProfileBuilder, PrinterModel, PrinterConnectionData, StyleScope, PrintStats, PrinterStatus, PrintQueue, PrintJob, Transport};
+#[cfg(feature = "std")]
+pub use instruction::{Instruction, PrintData, PrintDataBuilder, EscposImage, DitherMode, BayerSize, AlphaMode, ImageMode};
+#[cfg(feature = "alloc")]
+pub use justification::Justification;
+#[cfg(feature = "alloc")]
 pub use error::{Error};
-pub use formatter::{Formatter, TableOptions};
+#[cfg(feature = "alloc")]
+pub use formatter::{Formatter, TableOptions, TableSpec, ColumnSpec, ColumnWidth};
+#[cfg(feature = "std")]
+pub use style_sheet::{StyleSheet, TextStyle};
+#[cfg(feature = "std")]
+pub use template::Template;
+#[cfg(feature = "std")]
+pub use diagnostic::Diagnostic;
+#[cfg(feature = "std")]
+pub use validation::{validate_escpos, ValidationIssue};
 
 /// Contains raw esc/pos commands
+#[cfg(feature = "alloc")]
 pub mod command;
 
+#[cfg(feature = "std")]
 mod printer;
+#[cfg(feature = "std")]
 mod instruction;
+#[cfg(feature = "alloc")]
+mod justification;
+#[cfg(feature = "alloc")]
 mod error;
-mod formatter;
\ No newline at end of file
+#[cfg(feature = "alloc")]
+mod formatter;
+#[cfg(feature = "std")]
+mod style_sheet;
+#[cfg(feature = "std")]
+mod template;
+#[cfg(feature = "std")]
+mod diagnostic;
+#[cfg(feature = "std")]
+mod validation;
\ No newline at end of file
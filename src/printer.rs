@@ -1,35 +1,66 @@
 pub use self::printer_profile::{PrinterProfile, PrinterConnectionData, PrinterProfileBuilder};
 pub use self::printer_model::PrinterModel;
+pub use self::style_scope::StyleScope;
+pub use self::print_stats::PrintStats;
+pub use self::printer_status::PrinterStatus;
+pub use self::print_queue::{PrintQueue, PrintJob};
+pub use self::transport::Transport;
 
 mod printer_profile;
 mod printer_model;
+mod style_scope;
+mod print_stats;
+mod printer_status;
+mod print_queue;
+mod transport;
 
 use crate::{
     Instruction,
+    Justification,
     PrintData,
     EscposImage,
     Error,
-    command::{Command, Font},
-    Formatter
+    command::{Command, CodeTable, ErrorCorrection, Font, PrintColor, QrModel, UserCharBitmap},
+    Formatter,
+    TableOptions,
+    TableSpec,
+    Diagnostic,
+    StyleSheet,
+    diagnostic::report,
+    formatter::TableCells,
+    validation::printable_text
 };
 
-extern crate codepage_437;
-extern crate log;
-
-use log::{warn};
 use rusb::{UsbContext, Context, DeviceHandle, TransferType, Direction};
-use codepage_437::{IntoCp437, CP437_CONTROL};
+use qrcode::{QrCode, EcLevel};
+use codepage_437::CP437_CONTROL;
+use std::io::Write;
+
+/// Box-drawing horizontal line in CP437, prints as a continuous rule
+const HLINE_BYTE: u8 = 0xc4;
 
 /// Keeps the actual living connection to the device
 enum PrinterConnection {
     Usb {
         /// Bulk write endpoint
         endpoint: u8,
+        /// Bulk read endpoint, to get status answers
+        in_endpoint: Option<u8>,
         /// Device handle
         dh: DeviceHandle<Context>,
         /// Time to wait before giving up writing to the bulk endpoint
         timeout: std::time::Duration
     },
+    File {
+        /// Path of the file or device
+        path: std::path::PathBuf,
+        /// Open file, if it is kept open between writes
+        file: Option<std::fs::File>
+    },
+    /// Link provided by the user of the library
+    Custom {
+        transport: std::cell::RefCell<Box<dyn Transport>>
+    },
     #[allow(dead_code)]
     Network,
     Terminal
@@ -59,7 +90,21 @@ pub struct Printer {
     /// The auxiliary formatter to print nicely
     formatter: Formatter,
     /// If words should be splitted or not
-    space_split: bool
+    space_split: bool,
+    /// Code table currently selected in the printer
+    code_table: CodeTable,
+    /// Receives the diagnostics, instead of the log
+    diagnostics_handler: Option<Box<dyn Fn(Diagnostic) + Send>>,
+    /// Counters of the data sent so far
+    stats: std::cell::Cell<PrintStats>,
+    /// Named styles for printing
+    style_sheet: StyleSheet,
+    /// Receives a copy of all the bytes sent to the printer
+    tee: std::cell::RefCell<Option<Box<dyn Write + Send>>>,
+    /// Receives a readable copy of the text sent to the printer
+    mirror: std::cell::RefCell<Option<Box<dyn Write + Send>>>,
+    /// Width and height multipliers of the characters
+    text_size: (u8, u8)
 }
 
 impl Printer {
@@ -67,96 +112,373 @@ impl Printer {
     /// 
     /// Creates the printer with the given details, from the printer details provided, and in the given USB context.
     pub fn new(printer_profile: PrinterProfile) -> Result<Option<Printer>, Error> {
-        // Font and width, at least one required.
-        let font_and_width = if let Some(width) = printer_profile.columns_per_font.get(&Font::FontA) {
-            (Font::FontA, *width)
-        } else {
-            return Err(Error::NoFontFound);
-        };
+        Printer::new_with_diagnostics_handler(printer_profile, None)
+    }
+
+    /// Creates a new printer, retrying if it is not found
+    ///
+    /// A freshly plugged printer might not be listed yet by the time the devices are scanned. This function scans up to `attempts` times (at least once), waiting `delay` between each scan. The printer is returned as soon as it gets found, and `Ok(None)` if it never appears. Errors stop the retries.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let start = std::time::Instant::now();
+    /// // The terminal is always found, so there is no wait
+    /// let printer = Printer::new_with_retry(PrinterProfile::terminal_builder().build(), 5, std::time::Duration::from_secs(10))?;
+    /// assert!(printer.is_some());
+    /// assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn new_with_retry(printer_profile: PrinterProfile, attempts: u32, delay: std::time::Duration) -> Result<Option<Printer>, Error> {
+        let attempts = attempts.max(1);
+        for attempt in 0..attempts {
+            if let Some(printer) = Printer::new(printer_profile.clone())? {
+                return Ok(Some(printer));
+            }
+            if attempt + 1 < attempts {
+                std::thread::sleep(delay);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Opens every connected printer that matches the profile
+    ///
+    /// Useful with several identical printers, like one per station. Where [new](Printer::new) stops at the first usb device with the profile's vendor and product id, this function opens all of them (the ones for which [matches_usb_device](crate::PrinterProfile::matches_usb_device) holds), and gives back a printer for each one (or an empty list if none is connected). With [with_kernel_driver](crate::PrinterProfileBuilder::with_kernel_driver), every matching `usblp` device is used instead, see [find_usblp_devices](Printer::find_usblp_devices). Other connections lead to a single printer. A device that fails to open (for example, because another program claimed it) is left out of the list, and reported as a [DeviceSkipped](crate::Diagnostic::DeviceSkipped) diagnostic, so the rest of the printers can still be used. Diagnostics found while connecting go to the log.
+    ///
+    /// There is no filter by serial number, as profiles do not carry one. To use one printer in particular, open it on its own (for example, its `usblp` device with a [file_builder](crate::PrinterProfile::file_builder) profile).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let printers = Printer::new_all(PrinterProfile::terminal_builder().build())?;
+    /// assert_eq!(1, printers.len());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn new_all(printer_profile: PrinterProfile) -> Result<Vec<Printer>, Error> {
+        match printer_profile.printer_connection_data {
+            PrinterConnectionData::Usb{vendor_id, product_id, endpoint, timeout, keep_kernel_driver} => {
+                let font_and_width = Printer::default_font_and_width(&printer_profile)?;
+                if keep_kernel_driver {
+                    let paths = Printer::find_usblp_devices("/sys", vendor_id, product_id);
+                    if !paths.is_empty() {
+                        let mut printers = Vec::new();
+                        for path in paths {
+                            match Printer::new_file(path.clone(), true, printer_profile.clone(), font_and_width.clone(), Formatter::new(font_and_width.1), None) {
+                                Ok(printer) => printers.push(printer),
+                                Err(e) => report(None, Diagnostic::DeviceSkipped{device: path.display().to_string(), reason: e.to_string()})
+                            }
+                        }
+                        return Ok(printers);
+                    }
+                }
+                let context = Context::new().map_err(Error::RusbError)?;
+                let devices = context.devices().map_err(Error::RusbError)?;
+                let mut printers = Vec::new();
+                for device in devices.iter() {
+                    let location = format!("usb bus {} address {}", device.bus_number(), device.address());
+                    let s = match device.device_descriptor() {
+                        Ok(s) => s,
+                        Err(e) => {
+                            report(None, Diagnostic::DeviceSkipped{device: location, reason: e.to_string()});
+                            continue;
+                        }
+                    };
+                    if printer_profile.matches_usb_device(s.vendor_id(), s.product_id()) {
+                        match Printer::new_usb(&device, endpoint, timeout, printer_profile.clone(), font_and_width.clone(), Formatter::new(font_and_width.1), None) {
+                            Ok(printer) => printers.push(printer),
+                            Err(e) => report(None, Diagnostic::DeviceSkipped{device: location, reason: e.to_string()})
+                        }
+                    }
+                }
+                Ok(printers)
+            },
+            _ => Ok(Printer::new(printer_profile)?.into_iter().collect())
+        }
+    }
+
+    /// Creates a new printer, with a handler for diagnostics
+    ///
+    /// Same as [new](Printer::new), but diagnostics found while connecting (and afterwards) get sent to the handler instead of the log. See [set_diagnostics_handler](Printer::set_diagnostics_handler).
+    pub fn new_with_diagnostics_handler(printer_profile: PrinterProfile, diagnostics_handler: Option<Box<dyn Fn(Diagnostic) + Send>>) -> Result<Option<Printer>, Error> {
+        let font_and_width = Printer::default_font_and_width(&printer_profile)?;
         let formatter = Formatter::new(font_and_width.1);
         // Quick check for the profile containing at least one font
         match printer_profile.printer_connection_data {
-            PrinterConnectionData::Usb{vendor_id, product_id, endpoint, timeout} => {
+            PrinterConnectionData::Usb{vendor_id, product_id, endpoint, timeout, keep_kernel_driver} => {
+                // With the kernel driver bound, we write through its character device instead
+                if keep_kernel_driver {
+                    if let Some(path) = Printer::find_usblp_device("/sys", vendor_id, product_id) {
+                        return Ok(Some(Printer::new_file(path, true, printer_profile, font_and_width, formatter, diagnostics_handler)?));
+                    }
+                }
                 let context = Context::new().map_err(Error::RusbError)?;
         
                 let devices = context.devices().map_err(Error::RusbError)?;
                 for device in devices.iter() {
                     let s = device.device_descriptor().map_err(Error::RusbError)?;
-                    if s.vendor_id() == vendor_id && s.product_id() == product_id {
-                        // Before opening the device, we must find the bulk endpoint
-                        let config_descriptor = device.active_config_descriptor().map_err(Error::RusbError)?;
-                        let actual_endpoint = if let Some(endpoint) = endpoint {
-                            endpoint
-                        } else {
-                            let mut detected_endpoint: Option<u8> = None;
-                            // Horrible to have 3 nested for, but so be it
-                            for interface in config_descriptor.interfaces() {
-                                for descriptor in interface.descriptors() {
-                                    for endpoint in descriptor.endpoint_descriptors() {
-                                        if let (TransferType::Bulk, Direction::Out) = (endpoint.transfer_type(), endpoint.direction()) {
-                                            detected_endpoint = Some(endpoint.number());   
-                                        }
-                                    }
-                                }
-                            }
-            
-                            if let Some(detected_endpoint) = detected_endpoint {
-                                detected_endpoint
-                            } else {
-                                return Err(Error::NoBulkEndpoint);
-                            }
-                        };
-        
-                        // Now we continue opening the device
-        
-                        match device.open() {
-                            Ok(mut dh) => {
-                                if let Ok(active) = dh.kernel_driver_active(0) {
-                                    if active {
-                                        // The kernel is active, we have to detach it
-                                        match dh.detach_kernel_driver(0) {
-                                            Ok(_) => (),
-                                            Err(e) => return Err(Error::RusbError(e))
-                                        };
-                                    }
-                                } else {
-                                    warn!("Could not find out if kernel driver is active, might encounter a problem soon.");
-                                };
-                                // Now we claim the interface
-                                match dh.claim_interface(0) {
-                                    Ok(_) => (),
-                                    Err(e) => return Err(Error::RusbError(e))
-                                }
-                                return Ok(Some(Printer {
-                                    printer_connection: PrinterConnection::Usb {
-                                        endpoint: actual_endpoint,
-                                        dh,
-                                        timeout
-                                    },
-                                    printer_profile,
-                                    font_and_width,
-                                    formatter,
-                                    space_split: false
-                                }));
-                            },
-                            Err(e) => return Err(Error::RusbError(e))
-                        };
+                    if printer_profile.matches_usb_device(s.vendor_id(), s.product_id()) {
+                        return Ok(Some(Printer::new_usb(&device, endpoint, timeout, printer_profile, font_and_width, formatter, diagnostics_handler)?));
                     }
                 }
                 // No printer was found with such vid and pid
                 Ok(None)
             },
+            PrinterConnectionData::File{ref path, keep_open} => {
+                let path = path.clone();
+                Ok(Some(Printer::new_file(path, keep_open, printer_profile, font_and_width, formatter, diagnostics_handler)?))
+            },
             PrinterConnectionData::Network{..} => panic!("Unsupported!"),
             PrinterConnectionData::Terminal => Ok(Some(Printer{
                 printer_connection: PrinterConnection::Terminal,
+                code_table: printer_profile.default_code_table.clone(),
                 printer_profile,
                 font_and_width,
                 formatter,
-                space_split: false
+                space_split: false,
+                diagnostics_handler,
+                stats: std::cell::Cell::new(PrintStats::default()),
+                style_sheet: StyleSheet::new(),
+                tee: std::cell::RefCell::new(None),
+                mirror: std::cell::RefCell::new(None),
+                text_size: (1, 1)
             }))
         }
     }
 
+    /// Creates a printer that writes through the given transport
+    ///
+    /// The connection of the profile is ignored, everything else (fonts, code table, transfer size...) applies as usual. See [Transport](crate::Transport).
+    pub fn new_with_transport(printer_profile: PrinterProfile, transport: Box<dyn Transport>) -> Result<Printer, Error> {
+        let font_and_width = Printer::default_font_and_width(&printer_profile)?;
+        let printer = Printer {
+            printer_connection: PrinterConnection::Custom {
+                transport: std::cell::RefCell::new(transport)
+            },
+            code_table: printer_profile.default_code_table.clone(),
+            printer_profile,
+            formatter: Formatter::new(font_and_width.1),
+            font_and_width,
+            space_split: false,
+            diagnostics_handler: None,
+            stats: std::cell::Cell::new(PrintStats::default()),
+            style_sheet: StyleSheet::new(),
+            tee: std::cell::RefCell::new(None),
+            mirror: std::cell::RefCell::new(None),
+            text_size: (1, 1)
+        };
+        // The code table only gets sent if the printer is not in the usual one
+        if printer.code_table != CodeTable::USA {
+            printer.raw(Command::SelectCodeTable{code_table: printer.code_table.clone()}.as_bytes())?;
+        }
+        Ok(printer)
+    }
+
+    // Font and width the printer starts with, at least one required.
+    fn default_font_and_width(printer_profile: &PrinterProfile) -> Result<(Font, u8), Error> {
+        if let Some(width) = printer_profile.columns_per_font.get(&Font::FontA) {
+            Ok((Font::FontA, *width))
+        } else {
+            Err(Error::NoFontFound)
+        }
+    }
+
+    // Auxiliar function to open a usb device, already known to match the profile
+    fn new_usb(device: &rusb::Device<Context>, endpoint: Option<u8>, timeout: std::time::Duration, printer_profile: PrinterProfile, font_and_width: (Font, u8), formatter: Formatter, diagnostics_handler: Option<Box<dyn Fn(Diagnostic) + Send>>) -> Result<Printer, Error> {
+        // Before opening the device, we must find the bulk endpoint
+        let config_descriptor = device.active_config_descriptor().map_err(Error::RusbError)?;
+        // The read endpoint is optional, only status requests need it
+        let mut in_endpoint: Option<u8> = None;
+        for interface in config_descriptor.interfaces() {
+            for descriptor in interface.descriptors() {
+                for endpoint in descriptor.endpoint_descriptors() {
+                    if let (TransferType::Bulk, Direction::In) = (endpoint.transfer_type(), endpoint.direction()) {
+                        in_endpoint = Some(endpoint.address());
+                    }
+                }
+            }
+        }
+        let actual_endpoint = if let Some(endpoint) = endpoint {
+            endpoint
+        } else {
+            let mut detected_endpoint: Option<u8> = None;
+            // Horrible to have 3 nested for, but so be it
+            for interface in config_descriptor.interfaces() {
+                for descriptor in interface.descriptors() {
+                    for endpoint in descriptor.endpoint_descriptors() {
+                        if let (TransferType::Bulk, Direction::Out) = (endpoint.transfer_type(), endpoint.direction()) {
+                            detected_endpoint = Some(endpoint.number());   
+                        }
+                    }
+                }
+            }
+            
+            if let Some(detected_endpoint) = detected_endpoint {
+                detected_endpoint
+            } else {
+                return Err(Error::NoBulkEndpoint);
+            }
+        };
+        
+        // Now we continue opening the device
+        
+        let mut dh = device.open().map_err(Error::RusbError)?;
+        if let Ok(active) = dh.kernel_driver_active(0) {
+            if active {
+                // The kernel is active, we have to detach it
+                match dh.detach_kernel_driver(0) {
+                    Ok(_) => (),
+                    Err(e) => return Err(Error::RusbError(e))
+                };
+            }
+        } else {
+            report(diagnostics_handler.as_deref().map(|handler| handler as &dyn Fn(Diagnostic)), Diagnostic::KernelDriverUnknown);
+        };
+        // Now we claim the interface
+        match dh.claim_interface(0) {
+            Ok(_) => (),
+            Err(e) => return Err(Error::RusbError(e))
+        }
+        let code_table = printer_profile.default_code_table.clone();
+        let printer = Printer {
+            printer_connection: PrinterConnection::Usb {
+                endpoint: actual_endpoint,
+                in_endpoint,
+                dh,
+                timeout
+            },
+            printer_profile,
+            font_and_width,
+            formatter,
+            space_split: false,
+            code_table,
+            diagnostics_handler,
+            stats: std::cell::Cell::new(PrintStats::default()),
+            style_sheet: StyleSheet::new(),
+            tee: std::cell::RefCell::new(None),
+            mirror: std::cell::RefCell::new(None),
+            text_size: (1, 1)
+        };
+        // The code table only gets sent if the printer is not in the usual one
+        if printer.code_table != CodeTable::USA {
+            printer.raw(Command::SelectCodeTable{code_table: printer.code_table.clone()}.as_bytes())?;
+        }
+        Ok(printer)
+    }
+
+    // Auxiliar function to create a printer that writes to a file or device
+    fn new_file(path: std::path::PathBuf, keep_open: bool, printer_profile: PrinterProfile, font_and_width: (Font, u8), formatter: Formatter, diagnostics_handler: Option<Box<dyn Fn(Diagnostic) + Send>>) -> Result<Printer, Error> {
+        let file = if keep_open {
+            Some(open_append(&path)?)
+        } else {
+            None
+        };
+        let code_table = printer_profile.default_code_table.clone();
+        let printer = Printer {
+            printer_connection: PrinterConnection::File {
+                path,
+                file
+            },
+            printer_profile,
+            font_and_width,
+            formatter,
+            space_split: false,
+            code_table,
+            diagnostics_handler,
+            stats: std::cell::Cell::new(PrintStats::default()),
+            style_sheet: StyleSheet::new(),
+            tee: std::cell::RefCell::new(None),
+            mirror: std::cell::RefCell::new(None),
+            text_size: (1, 1)
+        };
+        // The code table only gets sent if the printer is not in the usual one
+        if printer.code_table != CodeTable::USA {
+            printer.raw(Command::SelectCodeTable{code_table: printer.code_table.clone()}.as_bytes())?;
+        }
+        Ok(printer)
+    }
+
+    /// Looks for the `usblp` character device of a usb printer, through sysfs (Linux only)
+    ///
+    /// The `sysfs` argument is the mount point of sysfs, usually `/sys`. When the `usblp` kernel driver is bound to the printer, the corresponding `/dev/usb/lpN` path is returned. If the driver is not bound, or sysfs is not available (like in other operating systems), `None` is returned.
+    /// ```rust
+    /// use escpos_rs::Printer;
+    /// use std::fs;
+    ///
+    /// // A fake sysfs, with the driver bound to lp1
+    /// let sysfs = std::env::temp_dir().join("escpos-rs-sysfs-example");
+    /// # let _ = fs::remove_dir_all(&sysfs);
+    /// let device = sysfs.join("devices/usb1/1-1");
+    /// fs::create_dir_all(device.join("1-1:1.0")).unwrap();
+    /// fs::write(device.join("idVendor"), "04b8\n").unwrap();
+    /// fs::write(device.join("idProduct"), "0e15\n").unwrap();
+    /// fs::create_dir_all(sysfs.join("class/usbmisc/lp1")).unwrap();
+    /// std::os::unix::fs::symlink(device.join("1-1:1.0"), sysfs.join("class/usbmisc/lp1/device")).unwrap();
+    ///
+    /// assert_eq!(Some("/dev/usb/lp1".into()), Printer::find_usblp_device(&sysfs, 0x04b8, 0x0e15));
+    /// // Another printer, without the driver
+    /// assert_eq!(None, Printer::find_usblp_device(&sysfs, 0x0416, 0x5011));
+    /// # fs::remove_dir_all(&sysfs).unwrap();
+    /// ```
+    pub fn find_usblp_device<A: AsRef<std::path::Path>>(sysfs: A, vendor_id: u16, product_id: u16) -> Option<std::path::PathBuf> {
+        Printer::find_usblp_devices(sysfs, vendor_id, product_id).into_iter().next()
+    }
+
+    /// Looks for the `usblp` character devices of all the usb printers with the given ids, through sysfs (Linux only)
+    ///
+    /// Same as [find_usblp_device](Printer::find_usblp_device), but every matching printer is returned, sorted by device number (so `lp2` comes before `lp10`). The list is empty if no driver is bound or sysfs is not available.
+    /// ```rust
+    /// use escpos_rs::Printer;
+    /// use std::fs;
+    ///
+    /// // A fake sysfs, with two identical printers and a different one
+    /// let sysfs = std::env::temp_dir().join("escpos-rs-sysfs-all-example");
+    /// # let _ = fs::remove_dir_all(&sysfs);
+    /// for (port, lp, vendor_id, product_id) in vec![("1-1", "lp10", "04b8", "0e15"), ("1-2", "lp1", "0416", "5011"), ("1-3", "lp2", "04b8", "0e15")] {
+    ///     let device = sysfs.join("devices/usb1").join(port);
+    ///     let interface = device.join(format!("{}:1.0", port));
+    ///     fs::create_dir_all(&interface).unwrap();
+    ///     fs::write(device.join("idVendor"), format!("{}\n", vendor_id)).unwrap();
+    ///     fs::write(device.join("idProduct"), format!("{}\n", product_id)).unwrap();
+    ///     fs::create_dir_all(sysfs.join("class/usbmisc").join(lp)).unwrap();
+    ///     std::os::unix::fs::symlink(&interface, sysfs.join("class/usbmisc").join(lp).join("device")).unwrap();
+    /// }
+    ///
+    /// assert_eq!(vec![std::path::PathBuf::from("/dev/usb/lp2"), "/dev/usb/lp10".into()], Printer::find_usblp_devices(&sysfs, 0x04b8, 0x0e15));
+    /// assert_eq!(vec![std::path::PathBuf::from("/dev/usb/lp1")], Printer::find_usblp_devices(&sysfs, 0x0416, 0x5011));
+    /// assert!(Printer::find_usblp_devices(&sysfs, 0x1234, 0x5678).is_empty());
+    /// # fs::remove_dir_all(&sysfs).unwrap();
+    /// ```
+    pub fn find_usblp_devices<A: AsRef<std::path::Path>>(sysfs: A, vendor_id: u16, product_id: u16) -> Vec<std::path::PathBuf> {
+        let read_id = |path: std::path::PathBuf| -> Option<u16> {
+            let content = std::fs::read_to_string(path).ok()?;
+            u16::from_str_radix(content.trim(), 16).ok()
+        };
+        let entries = match std::fs::read_dir(sysfs.as_ref().join("class/usbmisc")) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new()
+        };
+        let mut paths = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if !name.to_string_lossy().starts_with("lp") {
+                continue;
+            }
+            // The device link points to the usb interface, the ids are in the parent usb device
+            let interface = match std::fs::canonicalize(entry.path().join("device")) {
+                Ok(interface) => interface,
+                Err(_) => continue
+            };
+            if let Some(device) = interface.parent() {
+                if read_id(device.join("idVendor")) == Some(vendor_id) && read_id(device.join("idProduct")) == Some(product_id) {
+                    paths.push(std::path::Path::new("/dev/usb").join(name));
+                }
+            }
+        }
+        // By the number of the device, as sorting the names would put lp10 before lp2
+        let number = |path: &std::path::PathBuf| -> Option<u32> {
+            path.file_name()?.to_str()?.trim_start_matches("lp").parse().ok()
+        };
+        paths.sort_by(|a, b| number(a).cmp(&number(b)).then_with(|| a.cmp(b)));
+        paths
+    }
+
     /// Guesses the printer, and connects to it (not meant for production)
     ///
     /// Might help to find which printer you have if you have only one connected. The function will try to connect to a printer, based on the common ones recognized by this library.
@@ -164,14 +486,16 @@ impl Printer {
         // Match to force update then a new model gets added, just as a reminder
         /*****
         IF YOU ARE READING THIS, AND YOU GOT AN  ERROR BECAUSE A PRINTER WAS MISSING,
-        UPDATE THE FOR FOLLOWING THE MATCH TO TRY ALL PRINTERS
+        UPDATE PrinterModel::ALL TO TRY ALL PRINTERS
         *****/
         match PrinterModel::TMT20 {
             PrinterModel::TMT20 => (),
-            PrinterModel::ZKTeco => ()
+            PrinterModel::ZKTeco => (),
+            PrinterModel::Generic80mm => (),
+            PrinterModel::Generic58mm => ()
         }
-        // Keep up to date! All printers should appear here for the function to work
-        for printer_model in vec![PrinterModel::TMT20, PrinterModel::ZKTeco] {
+        // Keep up to date! All printers should appear in PrinterModel::ALL for the function to work
+        for printer_model in PrinterModel::ALL {
             let printer_profile = printer_model.usb_profile();
             let candidate = Printer::new(printer_profile)?;
             if candidate.is_some() {
@@ -184,10 +508,206 @@ impl Printer {
 
     /// Print an instruction
     ///
-    /// You can pass optional printer data to the printer to fill in the dynamic parts of the instruction.
+    /// You can pass optional printer data to the printer to fill in the dynamic parts of the instruction. Instructions with only static content print fine with `None`. Otherwise, the missing part is reported precisely, as with an empty [PrintData](crate::PrintData) (for example, [NoReplacementFound](crate::Error::NoReplacementFound) or [NoTables](crate::Error::NoTables)).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, Error, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-instruction-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    ///
+    /// let greeting = Instruction::text("Welcome!", Font::FontA, Justification::Left, None);
+    /// printer.instruction(&greeting, None)?;
+    ///
+    /// let products = Instruction::duo_table("products", ("Product", "Price"), Font::FontA);
+    /// assert!(matches!(printer.instruction(&products, None), Err(Error::NoTables)));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
     pub fn instruction(&self, instruction: &Instruction, print_data: Option<&PrintData>) -> Result<(), Error> {
-        let content = instruction.to_vec(&self.printer_profile, print_data)?;
-        self.raw(&content)
+        let empty_print_data = PrintData::builder().build();
+        let print_data = print_data.unwrap_or(&empty_print_data);
+        let mut content = self.printer_profile.init_bytes(&self.code_table);
+        content.append(&mut instruction.to_vec_with_handler(&self.printer_profile, Some(print_data), &self.code_table, self.handler())?);
+        self.raw(content)
+    }
+
+    /// Moves the printer to a background thread, to print without blocking
+    ///
+    /// Jobs get queued with [print_async_bg](crate::PrintQueue::print_async_bg), and the printer can be recovered with [into_printer](crate::PrintQueue::into_printer). See [PrintQueue](crate::PrintQueue).
+    pub fn into_queue(self) -> PrintQueue {
+        PrintQueue::new(self)
+    }
+
+    /// Prints two instructions next to each other, each in its own half of the paper
+    ///
+    /// Each instruction gets rendered in `half_width` columns, and their lines get printed together, separated by a gutter with the rest of the line. Useful to print two narrow receipts on wide paper. Only text can be laid out this way (see [UnsupportedForLayout](crate::Error::UnsupportedForLayout)), so images, QR codes, tables and cuts have to be printed on their own. The half width is reduced if needed, so that at least one space separates both sides.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-side-by-side-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 24).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// let left = Instruction::text("Ticket 1 Espresso", Font::FontA, Justification::Left, None);
+    /// let right = Instruction::text("Ticket 2", Font::FontA, Justification::Right, None);
+    /// printer.print_side_by_side(&left, &right, None, 10)?;
+    /// assert_eq!(&b"Ticket 1        Ticket 2\nEspresso\n"[..], std::fs::read(&path).unwrap().as_slice());
+    ///
+    /// assert!(printer.print_side_by_side(&left, &Instruction::cut(), None, 10).is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_side_by_side(&self, left: &Instruction, right: &Instruction, print_data: Option<&PrintData>, half_width: u8) -> Result<(), Error> {
+        let half_width = half_width.min(self.columns().saturating_sub(1) / 2);
+        let gutter = (self.columns() - 2 * half_width) as usize;
+        let left_lines = left.plain_lines(half_width, print_data)?;
+        let right_lines = right.plain_lines(half_width, print_data)?;
+
+        let mut content = String::new();
+        for idx in 0..left_lines.len().max(right_lines.len()) {
+            let left_line = left_lines.get(idx).map(String::as_str).unwrap_or("");
+            let right_line = right_lines.get(idx).map(String::as_str).unwrap_or("");
+            let line = format!("{:<1$}{2}{3}", left_line, half_width as usize, " ".repeat(gutter), right_line);
+            content += line.trim_end_matches(' ');
+            content.push('\n');
+        }
+        self.raw_str(content)
+    }
+
+    /// Prints an instruction once per item, with data shared by all of them
+    ///
+    /// For each item, the common data gets [merged](crate::PrintData::merge) with the item's data (which wins when both have the same entry), and the instruction gets printed. Useful for runs of receipts that share the store name or the date. The first error stops the batch.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, PrintData, Instruction, Justification, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-batch-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let instruction = Instruction::text("%store%: %item%", Font::FontA, Justification::Left, Some(vec!["%store%".into(), "%item%".into()].into_iter().collect()));
+    /// let common = PrintData::builder().replacement("%store%", "Coffee shop").build();
+    /// let items = vec![
+    ///     PrintData::builder().replacement("%item%", "Espresso").build(),
+    ///     PrintData::builder().replacement("%item%", "Latte").build()
+    /// ];
+    /// printer.print_batch(&instruction, &common, &items)?;
+    ///
+    /// let content = String::from_utf8_lossy(&std::fs::read(&path).unwrap()).to_string();
+    /// assert_eq!(2, content.matches("Coffee shop").count());
+    /// assert!(content.find("Coffee shop: Espresso").unwrap() < content.find("Coffee shop: Latte").unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_batch(&self, instruction: &Instruction, common: &PrintData, per_item: &[PrintData]) -> Result<(), Error> {
+        for item in per_item {
+            self.instruction(instruction, Some(&common.merge(item)))?;
+        }
+        Ok(())
+    }
+
+    /// Prints a two part ticket, with a stub printed over the gap between them
+    ///
+    /// The main instruction gets printed, then the paper goes back `gap_lines` lines (see [ReverseFeedLines](crate::command::Command::ReverseFeedLines)) so the stub starts right at the perforation, and finally the paper gets cut. Everything goes to the printer in a single buffer. Both instructions must be static, as no [PrintData](crate::PrintData) is given.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-stub-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let main = Instruction::vspace(3);
+    /// let stub = Instruction::command(Command::BoldOn);
+    /// printer.print_stub(&main, &stub, 2)?;
+    ///
+    /// let mut target = b"\n\n\n".to_vec();
+    /// target.append(&mut Command::ReverseFeedLines{lines: 2}.as_bytes());
+    /// target.append(&mut Command::BoldOn.as_bytes());
+    /// target.append(&mut Command::Cut.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_stub(&self, main: &Instruction, stub: &Instruction, gap_lines: u8) -> Result<(), Error> {
+        let print_data = PrintData::builder().build();
+        let mut content = self.printer_profile.init_bytes(&self.code_table);
+        content.append(&mut main.to_vec_with_handler(&self.printer_profile, Some(&print_data), &self.code_table, self.handler())?);
+        content.append(&mut Command::ReverseFeedLines{lines: gap_lines}.as_bytes());
+        content.append(&mut stub.to_vec_with_handler(&self.printer_profile, Some(&print_data), &self.code_table, self.handler())?);
+        content.append(&mut self.printer_profile.cut_bytes());
+        self.raw(content)
+    }
+
+    /// Print an instruction, sending each of its top-level parts as soon as it gets serialized
+    ///
+    /// Useful for long receipts, as the whole print is never held in memory at once. After each part is sent, the `progress` callback receives the number of parts sent so far and the total. A non-compound instruction counts as a single part. Missing print data is treated as in [instruction](Printer::instruction).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction};
+    /// let path = std::env::temp_dir().join("escpos-rs-streamed-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let instruction = Instruction::vspace(1) + Instruction::vspace(2) + Instruction::cut();
+    ///
+    /// let mut calls = Vec::new();
+    /// printer.instruction_streamed(&instruction, None, |done, total| calls.push((done, total)))?;
+    /// assert_eq!(vec![(1, 3), (2, 3), (3, 3)], calls);
+    /// assert_eq!(instruction.to_vec(&PrinterProfile::file_builder(&path).build(), None)?, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn instruction_streamed<F: FnMut(usize, usize)>(&self, instruction: &Instruction, print_data: Option<&PrintData>, mut progress: F) -> Result<(), Error> {
+        let empty_print_data = PrintData::builder().build();
+        let print_data = Some(print_data.unwrap_or(&empty_print_data));
+        let parts = match instruction {
+            Instruction::Compound{instructions} => instructions.iter().collect(),
+            other => vec![other]
+        };
+        let total = parts.len();
+        for (idx, part) in parts.into_iter().enumerate() {
+            let mut content = if idx == 0 {
+                self.printer_profile.init_bytes(&self.code_table)
+            } else {
+                Vec::new()
+            };
+            content.append(&mut part.to_vec_with_handler(&self.printer_profile, print_data, &self.code_table, self.handler())?);
+            self.raw(content)?;
+            progress(idx + 1, total);
+        }
+        Ok(())
+    }
+
+    /// Print several copies of an instruction
+    ///
+    /// The instruction gets serialized only once, which saves time for image-heavy instructions. If `between` is given, it gets printed between each pair of copies (a cut, for example). Missing print data is treated as in [instruction](Printer::instruction).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-instruction-copies-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).build();
+    /// let printer = Printer::new(printer_profile.clone())?.unwrap();
+    /// let instruction = Instruction::text("Order #12", Font::FontA, Justification::Center, None);
+    /// // Kitchen and customer copies
+    /// printer.instruction_copies(&instruction, None, 2, Some(&Instruction::cut()))?;
+    ///
+    /// let single = instruction.to_vec(&printer_profile, None)?;
+    /// let mut target = single.clone();
+    /// target.append(&mut Instruction::cut().to_vec(&printer_profile, None)?);
+    /// target.extend_from_slice(&single);
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn instruction_copies(&self, instruction: &Instruction, print_data: Option<&PrintData>, copies: u8, between: Option<&Instruction>) -> Result<(), Error> {
+        let empty_print_data = PrintData::builder().build();
+        let print_data = Some(print_data.unwrap_or(&empty_print_data));
+        let mut content = self.printer_profile.init_bytes(&self.code_table);
+        content.append(&mut instruction.to_vec_with_handler(&self.printer_profile, print_data, &self.code_table, self.handler())?);
+        let separator = if let Some(between) = between {
+            between.to_vec_with_handler(&self.printer_profile, print_data, &self.code_table, self.handler())?
+        } else {
+            Vec::new()
+        };
+        for copy in 0..copies {
+            if copy != 0 && !separator.is_empty() {
+                self.raw(&separator)?;
+            }
+            self.raw(&content)?;
+        }
+        Ok(())
     }
     
     /// Print some text.
@@ -200,13 +720,14 @@ impl Printer {
             content.into()
         };
         match self.printer_connection {
-            PrinterConnection::Usb{..} => {
-                let feed = content.into_cp437(&CP437_CONTROL).map_err(|e| Error::CP437Error(e.into_string()))?;
+            PrinterConnection::Usb{..} | PrinterConnection::File{..} | PrinterConnection::Custom{..} => {
+                let mut feed = self.printer_profile.init_bytes(&self.code_table);
+                feed.append(&mut self.code_table.encode(content)?);
                 self.raw(&feed)
             },
             PrinterConnection::Network => panic!("Unimplemented!"),
             PrinterConnection::Terminal => {
-                print!("{}", content);
+                self.terminal_print(&content);
                 Ok(())
             }
         }
@@ -220,93 +741,1511 @@ impl Printer {
         self.print(&feed)
     }
 
-    /// Sets the current printing font.
+    /// Print some text with a newline at the end, giving back the number of lines it took on paper
     ///
-    /// The function will return an error if the specified font does not exist in the printer profile.
-    pub fn set_font(&mut self, font: Font) -> Result<(), Error> {
-        if let Some(width) = self.printer_profile.columns_per_font.get(&font) {
-            self.font_and_width = (font, *width);
-            Ok(())
+    /// Lines get broken as in [println](Printer::println), either by the printer itself when the text exceeds the current font's width, or by whitespaces when [space splitting](Printer::set_space_split) is enabled. Useful to keep track of the paper used, or to position the following content.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::terminal_builder().with_font_width(Font::FontA, 16).build();
+    /// let mut printer = Printer::new(printer_profile)?.unwrap();
+    /// // 40 characters, broken by the printer every 16
+    /// assert_eq!(3, printer.print_counted("A sentence long enough for three lines!!")?);
+    /// printer.set_space_split(true);
+    /// // "A sentence that", "wraps into", "three lines"
+    /// assert_eq!(3, printer.print_counted("A sentence that wraps into three lines")?);
+    /// assert_eq!(1, printer.print_counted("")?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_counted<T: Into<String>>(&self, content: T) -> Result<u32, Error> {
+        let content = content.into();
+        let width = self.columns().max(1) as usize;
+        let lines = if self.space_split {
+            self.formatter.space_split(&content)
         } else {
-            Err(Error::UnsupportedFont)
+            content.clone()
+        }.split('\n').map(|line| {
+            // The printer breaks the lines that exceed the width
+            let chars = line.chars().count();
+            chars.div_ceil(width).max(1) as u32
+        }).sum();
+        self.println(content)?;
+        Ok(lines)
+    }
+
+    /// Print some text, aligned with the given justification
+    ///
+    /// The text gets split by whitespaces according to the current font's width, and each line gets aligned with spaces. Nothing changes in the printer's state, so the following prints are not affected. See [Formatter](crate::Formatter)'s [justify](crate::Formatter::justify).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Justification, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-println-just-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 12).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.println_just("Total", Justification::Right)?;
+    /// printer.println_just("Thanks for your visit", Justification::Center)?;
+    /// printer.println("Bye")?;
+    /// assert_eq!(&b"       Total\n Thanks for\n your visit\nBye\n"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn println_just<A: AsRef<str>>(&self, content: A, justification: Justification) -> Result<(), Error> {
+        self.raw_str(self.formatter.justify(content, justification))
+    }
+
+    /// Print some text in a single line, cutting what does not fit
+    ///
+    /// Unlike [println](Printer::println), the text never wraps: it gets truncated to the current font's width, and new lines inside it become spaces. Useful for devices with a single line of fixed width, like customer displays, where an extra line would scroll the content away.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-line-clamped-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 20).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.print_line_clamped("Espresso, double shot 2.50")?;
+    /// printer.print_line_clamped("Total\n2.50")?;
+    /// assert_eq!(&b"Espresso, double sho\nTotal 2.50\n"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_line_clamped<A: AsRef<str>>(&self, content: A) -> Result<(), Error> {
+        let line: String = content.as_ref()
+            .trim_end_matches('\n')
+            .chars()
+            .map(|c| if c == '\n' { ' ' } else { c })
+            .take(self.columns() as usize)
+            .collect();
+        self.raw_str(line + "\n")
+    }
+
+    /// Prints markdown text, with the current font
+    ///
+    /// Builds a [markdown instruction](crate::Instruction::markdown) and prints it, wrapping the lines with the current font's width. The printer does not keep track of the justification, so the text goes to the left. In the terminal, the text gets printed without the markers.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-markdown-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.print_markdown("Some **bold** text")?;
+    ///
+    /// let mut expected = Command::BoldOn.as_bytes();
+    /// expected.extend_from_slice(b"bold");
+    /// expected.append(&mut Command::BoldOff.as_bytes());
+    /// let content = std::fs::read(&path).unwrap();
+    /// assert!(content.windows(expected.len()).any(|window| window == expected.as_slice()));
+    /// assert!(content.ends_with(b" text\n"));
+    /// # std::fs::remove_file(&path).unwrap();
+    ///
+    /// // The terminal shows the text alone
+    /// #[derive(Clone, Default)]
+    /// struct Capture(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+    /// impl std::io::Write for Capture {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.0.lock().unwrap().write(buf)
+    ///     }
+    ///     fn flush(&mut self) -> std::io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    /// let capture = Capture::default();
+    /// let printer = Printer::new(PrinterProfile::terminal_builder_with(Box::new(capture.clone())).build())?.unwrap();
+    /// printer.print_markdown("Some **bold** text")?;
+    /// assert_eq!(&b"Some bold text\n"[..], capture.0.lock().unwrap().as_slice());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_markdown<A: Into<String>>(&self, content: A) -> Result<(), Error> {
+        let instruction = Instruction::markdown(content.into(), self.font_and_width.0.clone(), Justification::Left, None);
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                let lines = instruction.plain_lines(self.columns(), None)?;
+                self.terminal_print(&lines.into_iter().map(|line| line + "\n").collect::<String>());
+                Ok(())
+            },
+            _other => self.instruction(&instruction, None)
         }
     }
 
-    /// Enables or disables space splitting for long text printing.
+    /// Sets a writer that receives a copy of all the bytes sent to the printer
     ///
-    /// By default, the printer writes text in a single stream to the printer (which splits it wherever the maximum width is reached). To split by whitespaces, you can call this function with `true` as argument.
-    pub fn set_space_split(&mut self, state: bool) {
-        self.space_split = state;
+    /// Useful to capture a session for debugging, without a second printer. Only the bytes that reached the printer get copied, and errors while writing to the tee are returned as [IoError](crate::Error::IoError). Passing `None` removes the tee.
+    pub fn set_tee(&mut self, tee: Option<Box<dyn Write + Send>>) {
+        self.tee = std::cell::RefCell::new(tee);
     }
 
-    /// Jumps _n_ number of lines (to leave whitespaces). Basically `n * '\n'` passed to `print`
-    pub fn jump(&self, n: u8) -> Result<(), Error> {
-        let feed = vec![b'\n', n];
-        self.raw(&feed)
+    /// Writes a human readable marker to the tee, and nothing to the printer
+    ///
+    /// The marker gets written as a line `=== marker ===`, so captured sessions show where each block of bytes begins. If no tee is set, this does nothing.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let path = std::env::temp_dir().join("escpos-rs-tee-marker-example.bin");
+    /// let tee_path = std::env::temp_dir().join("escpos-rs-tee-marker-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    /// # let _ = std::fs::remove_file(&tee_path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.set_tee(Some(Box::new(std::fs::File::create(&tee_path)?)));
+    /// printer.tee_marker("receipt start")?;
+    /// printer.println("Total")?;
+    ///
+    /// assert_eq!(&b"=== receipt start ===\nTotal\n"[..], std::fs::read(&tee_path)?.as_slice());
+    /// assert_eq!(&b"Total\n"[..], std::fs::read(&path)?.as_slice());
+    /// # std::fs::remove_file(&path)?;
+    /// # std::fs::remove_file(&tee_path)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn tee_marker<A: AsRef<str>>(&self, marker: A) -> Result<(), Error> {
+        self.write_tee(format!("=== {} ===\n", marker.as_ref()).as_bytes())
     }
 
-    /// Cuts the paper, in case the instruction is supported by the printer
-    pub fn cut(&self) -> Result<(), Error> {
-        self.raw(&Command::Cut.as_bytes())
+    // Copies the bytes to the tee, if there is one
+    fn write_tee(&self, bytes: &[u8]) -> Result<(), Error> {
+        if let Some(tee) = self.tee.borrow_mut().as_mut() {
+            tee.write_all(bytes).map_err(Error::IoError)?;
+        }
+        Ok(())
     }
 
-    /// Prints a table with two columns.
+    /// Sets a writer that receives the text sent to the printer, in a readable form
     ///
-    /// For more details, check [Formatter](crate::Formatter)'s [duo_table](crate::Formatter::duo_table).
-    pub fn duo_table<A: Into<String>, B: Into<String>, C: IntoIterator<Item = (D, E)>, D: Into<String>, E: Into<String>>(&self, headers: (A, B), rows: C) -> Result<(), Error> {
-        let content = self.formatter.duo_table(headers, rows);
+    /// Unlike the [tee](Printer::set_tee), which copies the raw bytes, the mirror gets the text decoded as utf-8, without the esc/pos commands and control bytes, so it can be shown to an operator as a preview of each receipt. The decoding is best effort: bytes above 127 are read as code page 437, and images show up as blank lines. Errors while writing to the mirror are returned as [IoError](crate::Error::IoError). Passing `None` removes the mirror.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // Writer that keeps what it receives, so it can be checked later
+    /// #[derive(Clone, Default)]
+    /// struct Captured(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl std::io::Write for Captured {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.0.lock().unwrap().extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    ///     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join("escpos-rs-set-mirror-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let captured = Captured::default();
+    /// printer.set_mirror(Some(Box::new(captured.clone())));
+    /// printer.raw(Command::BoldOn.as_bytes())?;
+    /// printer.println("Hello")?;
+    /// printer.raw(Command::BoldOff.as_bytes())?;
+    ///
+    /// assert_eq!("Hello\n", String::from_utf8(captured.0.lock().unwrap().clone()).unwrap());
+    ///
+    /// // Arguments of the commands stay out of the mirror, like the data of a barcode
+    /// printer.raw(&[0x1d, 0x6b, 0x04, b'A', b'1', b'2', 0x00])?;
+    /// printer.raw(&[0x1d, 0x6b, 0x49, 0x03, b'{', b'B', b'1'])?;
+    /// printer.raw(&[0x1b, 0x72, b'1'])?;
+    /// printer.println("Bye")?;
+    /// assert_eq!("Hello\nBye\n", String::from_utf8(captured.0.lock().unwrap().clone()).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_mirror(&mut self, mirror: Option<Box<dyn Write + Send>>) {
+        self.mirror = std::cell::RefCell::new(mirror);
+    }
+
+    // Copies the text of the bytes to the mirror, if there is one
+    fn write_mirror(&self, bytes: &[u8]) -> Result<(), Error> {
+        if let Some(mirror) = self.mirror.borrow_mut().as_mut() {
+            mirror.write_all(printable_text(bytes).as_bytes()).map_err(Error::IoError)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the style sheet, with the named styles for [println_style](Printer::println_style)
+    pub fn set_style_sheet(&mut self, style_sheet: StyleSheet) {
+        self.style_sheet = style_sheet;
+    }
+
+    /// Prints a line of text with a named style from the style sheet
+    ///
+    /// The attributes of the style get turned on before the text, and turned off right after it. The font and the character size go back to the printer's current ones, but the printer does not keep track of bold, underline or justification, so those go back to their defaults (off, and left justified) rather than to whatever was sent before with raw commands. An unknown style results in a [NoStyleFound](crate::Error::NoStyleFound) error. In the terminal, the style is ignored.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, StyleSheet, TextStyle, Justification, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-println-style-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.set_style_sheet(StyleSheet::new().with_style("title", TextStyle {
+    ///     bold: true,
+    ///     justification: Some(Justification::Center),
+    ///     ..Default::default()
+    /// }));
+    /// printer.println_style("title", "Coffee shop")?;
+    ///
+    /// let mut target = Command::BoldOn.as_bytes();
+    /// target.append(&mut Command::SelectJustification{justification: Justification::Center}.as_bytes());
+    /// target.extend_from_slice(b"Coffee shop\n");
+    /// target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+    /// target.append(&mut Command::BoldOff.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    ///
+    /// assert!(printer.println_style("subtitle", "Menu").is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn println_style<A: AsRef<str>>(&self, name: &str, content: A) -> Result<(), Error> {
+        let style = self.style_sheet.get(name).ok_or_else(|| Error::NoStyleFound(name.to_string()))?;
         match &self.printer_connection {
             PrinterConnection::Terminal => {
-                println!("{}", content);
+                self.terminal_print(&format!("{}\n", content.as_ref()));
                 Ok(())
             },
             _other => {
-                self.raw(&content)
+                let mut feed = style.on_bytes(&self.printer_profile);
+                feed.append(&mut self.code_table.encode(format!("{}\n", content.as_ref()))?);
+                feed.append(&mut style.off_bytes(&self.font_and_width.0, self.text_size, &self.printer_profile));
+                self.raw(feed)
             }
         }
     }
 
-    /// Prints a table with three columns.
+    /// Sets the current printing font.
     ///
-    /// For more details, check [Formatter](crate::Formatter)'s [trio_table](crate::Formatter::trio_table).
-    pub fn trio_table<A: Into<String>, B: Into<String>, C: Into<String>, D: IntoIterator<Item = (E, F, G)>, E: Into<String>, F: Into<String>, G: Into<String>>(&self, headers: (A, B, C), rows: D) -> Result<(), Error> {
-        let content = self.formatter.trio_table(headers, rows);
+    /// The function will return an error if the specified font does not exist in the printer profile. The formatter width follows the selected font, so tables and space splitting keep their alignment.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_font_width(Font::FontB, 42)
+    ///     .build();
+    /// let mut printer = Printer::new(printer_profile)?.unwrap();
+    /// assert_eq!(32, printer.formatter().width());
+    /// printer.set_font(Font::FontB)?;
+    /// assert_eq!(42, printer.formatter().width());
+    /// printer.set_font(Font::FontA)?;
+    /// let table = printer.formatter().duo_table(("Product", "Price"), vec![("Milk", "5.00")]);
+    /// assert!(table.lines().all(|line| line.len() == 32));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_font(&mut self, font: Font) -> Result<(), Error> {
+        self.set_font_width(font).map(|_| ())
+    }
+
+    /// Sets the current printing font, and gives back its width in columns.
+    ///
+    /// The formatter used for tables and space splitting gets updated to the new width as well.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_font_width(Font::FontB, 42)
+    ///     .build();
+    /// let mut printer = Printer::new(printer_profile)?.unwrap();
+    /// assert_eq!(42, printer.set_font_width(Font::FontB)?);
+    /// // Tables now span the new width
+    /// let table = printer.formatter().duo_table(("Product", "Price"), vec![("Milk", "5.00")]);
+    /// assert!(table.lines().all(|line| line.len() == 42));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_font_width(&mut self, font: Font) -> Result<u8, Error> {
+        if let Some(width) = self.printer_profile.columns_per_font.get(&font) {
+            self.font_and_width = (font, *width);
+            self.formatter.set_width(self.columns());
+            Ok(*width)
+        } else {
+            Err(Error::UnsupportedFont)
+        }
+    }
+
+    /// Prints a numbered ruler with the given font, to find out how many columns fit in a line.
+    ///
+    /// The ruler spans the number of columns that the profile has for the font. If the ruler breaks into more lines, or does not reach the end of the paper, the width can be adjusted with [set_font_columns](Printer::set_font_columns). See also [Formatter](crate::Formatter)'s [ruler](crate::Formatter::ruler).
+    pub fn calibrate_columns(&self, font: Font) -> Result<(), Error> {
+        let columns = *self.printer_profile.columns_per_font.get(&font).ok_or(Error::UnsupportedFont)?;
+        let ruler = Formatter::new(columns).ruler();
         match &self.printer_connection {
             PrinterConnection::Terminal => {
-                println!("{}", content);
+                self.terminal_print(&ruler);
                 Ok(())
             },
             _other => {
-                self.raw(&content)
+                let mut feed = self.printer_profile.font_bytes(&font);
+                feed.append(&mut self.code_table.encode(ruler)?);
+                feed.append(&mut self.printer_profile.font_bytes(&self.font_and_width.0));
+                self.raw(&feed)
             }
         }
     }
 
-    pub fn image(&self, escpos_image: EscposImage) -> Result<(), Error> {
-        self.raw(&escpos_image.feed(self.printer_profile.width))
+    /// Changes the number of columns for a font, without recompiling the profile.
+    ///
+    /// If the font is the active one, the formatter gets updated as well.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::terminal_builder().build();
+    /// let mut printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.set_font_columns(Font::FontA, 30);
+    /// assert_eq!(30, printer.formatter().width());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_font_columns(&mut self, font: Font, columns: u8) {
+        if font == self.font_and_width.0 {
+            self.font_and_width.1 = columns;
+            self.formatter.set_width(self.columns());
+        }
+        self.printer_profile.columns_per_font.insert(font, columns);
     }
 
-    /// Sends raw information to the printer
+    /// Sets the current printing font, overriding its number of columns.
     ///
-    /// As simple as it sounds
-    /// ```rust,no_run
-    /// use escpos_rs::{Printer,PrinterProfile};
-    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001).build();
-    /// let printer = Printer::new(printer_profile).unwrap().unwrap();
-    /// printer.raw(&[0x01, 0x02])?;
+    /// Works as a quick calibration, when the width in the profile turns out to be wrong. The override lasts for this printer only, and the font gets added to the profile if it was not there.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-set-font-with-width-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.set_font_with_width(Font::FontB, 20);
+    /// assert_eq!(Some(20), printer.profile().columns_for(&Font::FontB));
+    /// // Tables and wrapping use the new width
+    /// let table = printer.formatter().duo_table(("Product", "Price"), vec![("Milk", "5.00")]);
+    /// assert!(table.lines().all(|line| line.len() == 20));
+    /// printer.set_space_split(true);
+    /// printer.println("Thanks for your visit")?;
+    /// assert_eq!(&b"Thanks for your\nvisit\n"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
     /// # Ok::<(), escpos_rs::Error>(())
     /// ```
-    pub fn raw<A: AsRef<[u8]>>(&self, bytes: A) -> Result<(), Error> {
-        match &self.printer_connection {
-            PrinterConnection::Usb{endpoint, dh, timeout} => {
-                dh.write_bulk(
-                    *endpoint,
-                    bytes.as_ref(),
-                    *timeout
-                ).map_err(Error::RusbError)?;
-                Ok(())
-            },
-            _other => panic!("Unimplemented")
-        }
+    pub fn set_font_with_width(&mut self, font: Font, width: u8) {
+        self.printer_profile.columns_per_font.insert(font.clone(), width);
+        self.font_and_width = (font, width);
+        self.formatter.set_width(self.columns());
+    }
+
+    /// Gives back a reference to the formatter used for tables and space splitting.
+    pub fn formatter(&self) -> &Formatter {
+        &self.formatter
     }
+
+    /// Sets the table options of the formatter, used for the tables printed by the printer
+    ///
+    /// See [TableOptions](crate::TableOptions), which also allows a code table per column.
+    pub fn set_table_options(&mut self, table_options: TableOptions) {
+        self.formatter.set_table_options(table_options);
+    }
+
+    /// Selects the code table used to encode text.
+    ///
+    /// The corresponding `ESC t` command is sent to the printer, and further text printing gets encoded with the new code table, including the text of [instructions](Printer::instruction).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, command::{Command, CodeTable, Font}};
+    /// let path = std::env::temp_dir().join("escpos-rs-set-code-table-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.set_code_table(CodeTable::PC850)?;
+    /// printer.instruction(&Instruction::text("Á", Font::FontA, Justification::Left, None), None)?;
+    ///
+    /// let select = Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes();
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// assert!(bytes.starts_with(&select));
+    /// // Á is 0xb5 in PC850
+    /// assert!(bytes.ends_with(&[0xb5, b'\n']));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_code_table(&mut self, code_table: CodeTable) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Terminal => (),
+            _other => self.raw(Command::SelectCodeTable{code_table: code_table.clone()}.as_bytes())?
+        }
+        self.code_table = code_table;
+        Ok(())
+    }
+
+    /// Turns condensed printing on or off.
+    ///
+    /// Condensed characters are narrower, so more columns fit in a line. The formatter width does not change, so tables and space splitting keep using the font's columns; to use the extra columns, update them with [set_font_columns](Printer::set_font_columns). In the terminal, this does nothing.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let path = std::env::temp_dir().join("escpos-rs-set-condensed-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.set_condensed(true)?;
+    /// printer.set_condensed(false)?;
+    /// assert_eq!(vec![0x0f, 0x12], std::fs::read(&path).unwrap());
+    /// assert_eq!(32, printer.formatter().width());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_condensed(&self, on: bool) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Terminal => Ok(()),
+            _other => self.raw(Command::Condensed{on}.as_bytes())
+        }
+    }
+
+    /// Sets the width and height multipliers of the characters, from 1 to 8.
+    ///
+    /// Wider characters take more room, so the formatter width gets divided by the width multiplier (keeping at least one column), and tables, space splitting and clamped lines keep fitting in the paper. Changing the font afterwards keeps the multipliers, and so do the [named styles](Printer::println_style) with a size of their own. In the terminal, only the formatter width changes.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, StyleSheet, TextStyle, command::{Command, Font}};
+    /// let path = std::env::temp_dir().join("escpos-rs-set-text-size-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 20).build();
+    /// let mut printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.set_space_split(true);
+    /// // Fits in a line of 20 columns
+    /// printer.println("Two coffees to go")?;
+    /// printer.set_text_size(2, 2)?;
+    /// assert_eq!(10, printer.formatter().width());
+    /// // But not in one of 10
+    /// printer.println("Two coffees to go")?;
+    ///
+    /// let mut expected = b"Two coffees to go\n".to_vec();
+    /// expected.append(&mut Command::SelectCharacterSize{width: 2, height: 2}.as_bytes());
+    /// expected.extend_from_slice(b"Two\ncoffees\nto go\n");
+    /// assert_eq!(expected, std::fs::read(&path).unwrap());
+    ///
+    /// // Named styles with a size go back to the current one
+    /// printer.set_style_sheet(StyleSheet::new().with_style("total", TextStyle {
+    ///     size: Some((3, 3)),
+    ///     ..Default::default()
+    /// }));
+    /// printer.println_style("total", "5.00")?;
+    /// assert!(std::fs::read(&path).unwrap().ends_with(&Command::SelectCharacterSize{width: 2, height: 2}.as_bytes()));
+    ///
+    /// // Even the widest characters leave a column
+    /// printer.set_text_size(8, 8)?;
+    /// printer.set_font_columns(Font::FontA, 4);
+    /// assert_eq!(1, printer.formatter().width());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_text_size(&mut self, width: u8, height: u8) -> Result<(), Error> {
+        let (width, height) = (width.clamp(1, 8), height.clamp(1, 8));
+        match &self.printer_connection {
+            PrinterConnection::Terminal => (),
+            _other => self.raw(Command::SelectCharacterSize{width, height}.as_bytes())?
+        }
+        self.text_size = (width, height);
+        self.formatter.set_width(self.columns());
+        Ok(())
+    }
+
+    /// Gives back the width and height multipliers of the characters
+    pub fn text_size(&self) -> (u8, u8) {
+        self.text_size
+    }
+
+    // Auxiliar function to get the columns that fit in a line, with the current font and size
+    fn columns(&self) -> u8 {
+        (self.font_and_width.1 / self.text_size.0).max(1)
+    }
+
+    /// Gives back the code table currently used to encode text.
+    ///
+    /// When the printer gets created, the code table is the profile's default one.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::CodeTable};
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_default_code_table(CodeTable::PC850)
+    ///     .build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// assert_eq!(&CodeTable::PC850, printer.code_table());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn code_table(&self) -> &CodeTable {
+        &self.code_table
+    }
+
+    /// Sets a handler for the diagnostics of the printer
+    ///
+    /// By default, diagnostics (like images being scaled on the fly) get logged through the `log` crate. With a handler, they can be routed anywhere else.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Diagnostic};
+    /// let mut printer = Printer::new(PrinterProfile::terminal_builder().build())?.unwrap();
+    /// printer.set_diagnostics_handler(Box::new(|diagnostic: Diagnostic| eprintln!("{}", diagnostic)));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_diagnostics_handler(&mut self, diagnostics_handler: Box<dyn Fn(Diagnostic) + Send>) {
+        self.diagnostics_handler = Some(diagnostics_handler);
+    }
+
+    // Auxiliar function to pass the handler around
+    fn handler(&self) -> Option<&dyn Fn(Diagnostic)> {
+        self.diagnostics_handler.as_deref().map(|handler| handler as &dyn Fn(Diagnostic))
+    }
+
+    /// Gives back the profile the printer was created with
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::terminal_builder().with_width(576).with_font_width(Font::FontA, 48).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// assert_eq!(576, printer.profile().width());
+    /// assert_eq!(Some(48), printer.profile().columns_for(&Font::FontA));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn profile(&self) -> &PrinterProfile {
+        &self.printer_profile
+    }
+
+    /// Indicates if the printer prints to the terminal
+    ///
+    /// Handy to skip what makes no sense without paper, like images.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let printer = Printer::new(PrinterProfile::terminal_builder().build())?.unwrap();
+    /// assert!(printer.is_terminal());
+    /// assert!(!printer.is_usb());
+    /// assert!(!printer.is_file());
+    /// assert!(!printer.is_network());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn is_terminal(&self) -> bool {
+        matches!(self.printer_connection, PrinterConnection::Terminal)
+    }
+
+    /// Indicates if the printer is connected through usb
+    pub fn is_usb(&self) -> bool {
+        matches!(self.printer_connection, PrinterConnection::Usb{..})
+    }
+
+    /// Indicates if the printer writes to a file or device
+    pub fn is_file(&self) -> bool {
+        matches!(self.printer_connection, PrinterConnection::File{..})
+    }
+
+    /// Indicates if the printer is connected through the network
+    pub fn is_network(&self) -> bool {
+        matches!(self.printer_connection, PrinterConnection::Network)
+    }
+
+    /// Gives back the counters of the data sent to the printer
+    ///
+    /// Every write gets counted, be it an usb transfer (large buffers take several, see [with_max_transfer_size](crate::PrinterProfileBuilder::with_max_transfer_size)), a file write or a print to the terminal.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, PrintStats};
+    /// let printer = Printer::new(PrinterProfile::terminal_builder().build())?.unwrap();
+    /// assert_eq!(PrintStats::default(), printer.stats());
+    /// printer.println("First")?;
+    /// printer.println("Second")?;
+    /// let stats = printer.stats();
+    /// assert_eq!(2, stats.write_calls);
+    /// assert_eq!(13, stats.bytes_written);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn stats(&self) -> PrintStats {
+        self.stats.get()
+    }
+
+    // Auxiliar function to count a write
+    fn record_write(&self, bytes: usize) {
+        let mut stats = self.stats.get();
+        stats.bytes_written += bytes as u64;
+        stats.write_calls += 1;
+        self.stats.set(stats);
+    }
+
+    // Auxiliar function to print to the terminal, counting the write
+    fn terminal_print(&self, content: &str) {
+        if let Some(writer) = &self.printer_profile.terminal_writer {
+            // A failed preview should not stop the printing
+            if let Ok(mut writer) = writer.0.lock() {
+                let _ = writer.write_all(content.as_bytes());
+            }
+        } else {
+            print!("{}", content);
+        }
+        self.record_write(content.len());
+    }
+
+    /// Enables or disables space splitting for long text printing.
+    ///
+    /// By default, the printer writes text in a single stream to the printer (which splits it wherever the maximum width is reached). To split by whitespaces, you can call this function with `true` as argument.
+    pub fn set_space_split(&mut self, state: bool) {
+        self.space_split = state;
+    }
+
+    /// Tells if space splitting is enabled, see [set_space_split](Printer::set_space_split)
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let mut printer = Printer::new(PrinterProfile::terminal_builder().build())?.unwrap();
+    /// assert!(!printer.space_split());
+    /// printer.set_space_split(true);
+    /// assert!(printer.space_split());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn space_split(&self) -> bool {
+        self.space_split
+    }
+
+    /// Jumps _n_ number of lines (to leave whitespaces). Basically `n * '\n'` passed to `print`
+    pub fn jump(&self, n: u8) -> Result<(), Error> {
+        let feed = vec![b'\n'; n as usize];
+        self.raw(&feed)
+    }
+
+    /// Prints a solid horizontal line, as wide as the current font allows
+    ///
+    /// Uses the CP437 box-drawing character (`0xc4`), which has no gaps between characters, unlike dashes. See [hline_with](Printer::hline_with) to pick another character.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-hline-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 32).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.hline()?;
+    ///
+    /// let mut target = vec![0xc4; 32];
+    /// target.push(b'\n');
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn hline(&self) -> Result<(), Error> {
+        self.hline_with(HLINE_BYTE)
+    }
+
+    /// Prints a horizontal line made of the given byte, as wide as the current font allows
+    ///
+    /// The byte is sent as is, so it refers to a character of the printer's current code table (for example, `b'='` or the CP437 double line, `0xcd`).
+    pub fn hline_with(&self, byte: u8) -> Result<(), Error> {
+        let columns = self.columns() as usize;
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                self.terminal_print(&format!("{}\n", CP437_CONTROL.decode(byte).to_string().repeat(columns)));
+                Ok(())
+            },
+            _other => {
+                let mut feed = vec![byte; columns];
+                feed.push(b'\n');
+                self.raw(feed)
+            }
+        }
+    }
+
+    /// Prints the buffer and feeds _n_ lines, with the printer's configured line height.
+    ///
+    /// Uses the dedicated `ESC d` command, see [Command::FeedLines](crate::command::Command::FeedLines).
+    pub fn feed_lines(&self, n: u8) -> Result<(), Error> {
+        self.raw(Command::FeedLines{lines: n}.as_bytes())
+    }
+
+    /// Feeds the paper to the next black mark, to align pre-printed labels
+    ///
+    /// Requires a profile with a black mark sensor (see [with_black_mark_sensor](crate::PrinterProfileBuilder::with_black_mark_sensor)), otherwise a [NoBlackMarkSensor](crate::Error::NoBlackMarkSensor) error is returned. See [Command::FeedToMark](crate::command::Command::FeedToMark).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Error, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-feed-to-mark-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// assert!(matches!(printer.feed_to_mark(), Err(Error::NoBlackMarkSensor)));
+    ///
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_black_mark_sensor(true).build())?.unwrap();
+    /// printer.feed_to_mark()?;
+    /// assert_eq!(Command::FeedToMark.as_bytes(), std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn feed_to_mark(&self) -> Result<(), Error> {
+        if !self.printer_profile.has_black_mark_sensor {
+            return Err(Error::NoBlackMarkSensor);
+        }
+        self.raw(Command::FeedToMark.as_bytes())
+    }
+
+    /// Presents the printed label (or ticket) to the customer
+    ///
+    /// The paper held by the presenter gets moved out by `length` steps (see [Command::PresentPaper](crate::command::Command::PresentPaper)). Requires a profile with a presenter, see [with_presenter](crate::PrinterProfileBuilder::with_presenter), otherwise a [NoPresenter](crate::Error::NoPresenter) error is returned.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Error, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-present-label-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// assert!(matches!(printer.present_label(40), Err(Error::NoPresenter)));
+    /// assert!(matches!(printer.retract_label(), Err(Error::NoPresenter)));
+    ///
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_presenter(true).build())?.unwrap();
+    /// printer.present_label(40)?;
+    /// printer.retract_label()?;
+    /// let mut target = Command::PresentPaper{length: 40}.as_bytes();
+    /// target.append(&mut Command::RetractPaper.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn present_label(&self, length: u8) -> Result<(), Error> {
+        if !self.printer_profile.has_presenter {
+            return Err(Error::NoPresenter);
+        }
+        self.raw(Command::PresentPaper{length}.as_bytes())
+    }
+
+    /// Retracts the label (or ticket) held by the presenter, see [present_label](Printer::present_label)
+    pub fn retract_label(&self) -> Result<(), Error> {
+        if !self.printer_profile.has_presenter {
+            return Err(Error::NoPresenter);
+        }
+        self.raw(Command::RetractPaper.as_bytes())
+    }
+
+    /// Selects the color of the characters printed next
+    ///
+    /// Sends [Command::TextColor](crate::command::Command::TextColor), so following text gets printed in the given color until it is changed back. Requires a profile with two-color printing, see [with_color](crate::PrinterProfileBuilder::with_color), otherwise a [NoColorSupport](crate::Error::NoColorSupport) error is returned.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Error, command::PrintColor};
+    /// let path = std::env::temp_dir().join("escpos-rs-set-text-color-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// assert!(matches!(printer.set_text_color(PrintColor::Red), Err(Error::NoColorSupport)));
+    ///
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_color(true).build())?.unwrap();
+    /// printer.set_text_color(PrintColor::Red)?;
+    /// printer.set_text_color(PrintColor::Black)?;
+    /// assert_eq!(vec![
+    ///     0x1d, 0x28, 0x4e, 0x02, 0x00, 0x30, 0x32,
+    ///     0x1d, 0x28, 0x4e, 0x02, 0x00, 0x30, 0x31
+    /// ], std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_text_color(&self, color: PrintColor) -> Result<(), Error> {
+        if !self.printer_profile.supports_color {
+            return Err(Error::NoColorSupport);
+        }
+        self.raw(Command::TextColor{color}.as_bytes())
+    }
+
+    /// Discards the data the printer holds, but not yet printed
+    ///
+    /// Useful after a job fails half way, so the partial data does not get printed with the next job. Both the page being built in page mode (see [Command::Cancel](crate::command::Command::Cancel)) and the receive and print buffers (see [Command::ClearBuffers](crate::command::Command::ClearBuffers)) get cleared, while styles and other settings are kept. Requires real-time commands, see [with_real_time_commands](crate::PrinterProfileBuilder::with_real_time_commands).
+    pub fn clear_buffer(&self) -> Result<(), Error> {
+        if !self.printer_profile.supports_real_time {
+            return Err(Error::NoRealTimeCommands);
+        }
+        let mut feed = Command::Cancel.as_bytes();
+        feed.append(&mut Command::ClearBuffers.as_bytes());
+        self.raw(feed)
+    }
+
+    /// Recovers the printer from an error, like a paper jam or an open cover
+    ///
+    /// With `clear_buffers`, the pending data gets discarded, otherwise printing continues from the line where the error occurred. Unlike an initialization, the settings are kept. Requires real-time commands, see [with_real_time_commands](crate::PrinterProfileBuilder::with_real_time_commands).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Error};
+    /// let path = std::env::temp_dir().join("escpos-rs-recover-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// assert!(matches!(printer.recover(true), Err(Error::NoRealTimeCommands)));
+    ///
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_real_time_commands(true).build())?.unwrap();
+    /// printer.recover(true)?;
+    /// printer.clear_buffer()?;
+    /// assert_eq!(
+    ///     vec![0x10, 0x05, 0x02, 0x18, 0x10, 0x14, 0x08, 0x01, 0x03, 0x14, 0x01, 0x06, 0x02, 0x08],
+    ///     std::fs::read(&path).unwrap()
+    /// );
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn recover(&self, clear_buffers: bool) -> Result<(), Error> {
+        if !self.printer_profile.supports_real_time {
+            return Err(Error::NoRealTimeCommands);
+        }
+        self.raw(Command::Recover{clear_buffers}.as_bytes())
+    }
+
+    /// Turns bold on, until the returned scope gets dropped
+    ///
+    /// Everything printed through the scope is bold. Turning the style off cannot be forgotten, as it happens when the scope is dropped.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-bold-scope-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// {
+    ///     // Sends BoldOn
+    ///     let bold = printer.bold_scope()?;
+    ///     bold.println("Total: 5.00")?;
+    ///     // BoldOff gets sent here
+    /// }
+    /// printer.println("Thanks for your purchase")?;
+    ///
+    /// let mut target = Command::BoldOn.as_bytes();
+    /// target.extend_from_slice(b"Total: 5.00\n");
+    /// target.append(&mut Command::BoldOff.as_bytes());
+    /// target.extend_from_slice(b"Thanks for your purchase\n");
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn bold_scope(&mut self) -> Result<StyleScope<'_>, Error> {
+        self.style(&Command::BoldOn)?;
+        Ok(StyleScope::new(self, Command::BoldOff))
+    }
+
+    /// Turns underline on, until the returned scope gets dropped
+    ///
+    /// See [bold_scope](Printer::bold_scope) for more details.
+    pub fn underline_scope(&mut self) -> Result<StyleScope<'_>, Error> {
+        self.style(&Command::Underline1Dot)?;
+        Ok(StyleScope::new(self, Command::UnderlineOff))
+    }
+
+    /// Turns inverse printing (white over black) on, until the returned scope gets dropped
+    ///
+    /// See [bold_scope](Printer::bold_scope) for more details.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let mut printer = Printer::new(PrinterProfile::terminal_builder().build())?.unwrap();
+    /// // Styles are ignored in the terminal, but the scope still prints
+    /// printer.inverse_scope()?.println("SALE")?;
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn inverse_scope(&mut self) -> Result<StyleScope<'_>, Error> {
+        self.style(&Command::ReverseVideo{on: true})?;
+        Ok(StyleScope::new(self, Command::ReverseVideo{on: false}))
+    }
+
+    /// Sets the horizontal tab stops, as columns in ascending order
+    ///
+    /// Tab characters in the printed text then move to the next stop, which aligns simple columns without padding. See [Command::SetTabStops](crate::command::Command::SetTabStops).
+    /// ```rust,no_run
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001).build();
+    /// let printer = Printer::new(printer_profile).unwrap().unwrap();
+    /// printer.set_tab_stops(&[16, 24])?;
+    /// printer.println("Milk\t3\t5.00")?;
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn set_tab_stops(&self, positions: &[u8]) -> Result<(), Error> {
+        self.style(&Command::SetTabStops{positions: positions.to_vec()})
+    }
+
+    /// Writes a memory switch of the printer, changing its persistent configuration
+    ///
+    /// __Warning__: memory switches are stored in non-volatile memory, which supports a limited number of writes, and wrong values can leave the printer misbehaving (for example, with a different baud rate or code table) until the switch is set back. Check the printer's manual for the meaning of each bit before using this function, and avoid calling it on every print.
+    ///
+    /// The printer enters user setting mode, the switch gets written (see [Command::MemorySwitch](crate::command::Command::MemorySwitch)), and the mode is left, which restarts the printer. Not available for the terminal.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-memory-switch-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// printer.unsafe_set_memory_switch(1, 0b0000_0100)?;
+    ///
+    /// let mut target = Command::UserSettingMode{enter: true}.as_bytes();
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x45, 0x0a, 0x00, 0x03, 0x01]);
+    /// target.extend_from_slice(b"00000100");
+    /// target.append(&mut Command::UserSettingMode{enter: false}.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn unsafe_set_memory_switch(&self, switch: u8, value: u8) -> Result<(), Error> {
+        if let PrinterConnection::Terminal = self.printer_connection {
+            return Err(Error::UnsupportedForPrinterConnection);
+        }
+        let mut feed = Command::UserSettingMode{enter: true}.as_bytes();
+        feed.append(&mut Command::MemorySwitch{switch, value}.as_bytes());
+        feed.append(&mut Command::UserSettingMode{enter: false}.as_bytes());
+        self.raw(feed)
+    }
+
+    // Auxiliar function to send style commands, which the terminal ignores
+    fn style(&self, command: &Command) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Terminal => Ok(()),
+            _other => self.raw(command.as_bytes())
+        }
+    }
+
+    /// Cuts the paper, in case the instruction is supported by the printer
+    ///
+    /// If the profile has no cutter, some lines get fed instead to reach the tear bar (see [with_cutter](crate::PrinterProfileBuilder::with_cutter)).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-cut-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_cutter(false).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.cut()?;
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// assert_eq!(Command::FeedLines{lines: 4}.as_bytes(), bytes);
+    /// assert!(!bytes.starts_with(&[0x1d, 0x56]));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn cut(&self) -> Result<(), Error> {
+        if self.printer_profile.sync_before_cut {
+            self.sync()?;
+        }
+        self.raw(self.printer_profile.cut_bytes())
+    }
+
+    /// Waits until the printer processed everything sent so far
+    ///
+    /// The printer status gets requested (`DLE EOT`), and the answer only arrives once the previous data got consumed. Gives back `true` if the printer answered in time, and `false` if it did not, or if the status cannot be read: without [real-time commands](crate::PrinterProfileBuilder::with_real_time_commands), or with a connection that cannot read (only usb with a bulk in endpoint, and [transports](crate::Transport) that can read, are able to). In those cases, nothing gets sent. See also [with_sync_before_cut](crate::PrinterProfileBuilder::with_sync_before_cut).
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-sync-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path)
+    ///     .with_cutter(false)
+    ///     .with_real_time_commands(true)
+    ///     .with_sync_before_cut(true)
+    ///     .build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// // Files cannot answer, so the cut goes right away, without a status request
+    /// assert!(!printer.sync()?);
+    /// printer.cut()?;
+    /// assert_eq!(Command::FeedLines{lines: 4}.as_bytes(), std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    ///
+    /// With a connection that reads, the status gets requested and answered before the cut.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Transport, Error, command::Command};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // Answers "online" to each status request, and logs the traffic
+    /// struct Answering(Arc<Mutex<Vec<String>>>);
+    ///
+    /// impl Transport for Answering {
+    ///     fn write(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+    ///         self.0.lock().unwrap().push(format!("write {:02x?}", bytes));
+    ///         Ok(bytes.len())
+    ///     }
+    ///     fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+    ///         self.0.lock().unwrap().push("read".to_string());
+    ///         buffer[0] = 0x12;
+    ///         Ok(1)
+    ///     }
+    ///     fn can_read(&self) -> bool {
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001)
+    ///     .with_real_time_commands(true)
+    ///     .with_sync_before_cut(true)
+    ///     .build();
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let printer = Printer::new_with_transport(printer_profile, Box::new(Answering(log.clone())))?;
+    /// printer.cut()?;
+    ///
+    /// assert_eq!(vec![
+    ///     format!("write {:02x?}", Command::TransmitStatus{status: 1}.as_bytes()),
+    ///     "read".to_string(),
+    ///     format!("write {:02x?}", Command::Cut.as_bytes())
+    /// ], *log.lock().unwrap());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn sync(&self) -> Result<bool, Error> {
+        if !self.printer_profile.supports_real_time {
+            return Ok(false);
+        }
+        Ok(self.request_status(1)?.is_some())
+    }
+
+    /// Reads the status of the printer
+    ///
+    /// The four `DLE EOT` requests get sent, and their answers decoded. Requires [real-time commands](crate::PrinterProfileBuilder::with_real_time_commands), otherwise a [NoRealTimeCommands](crate::Error::NoRealTimeCommands) error is returned. Only usb connections with a bulk in endpoint, and [transports](crate::Transport) that can read, get the answers, other connections give an [UnsupportedForPrinterConnection](crate::Error::UnsupportedForPrinterConnection) error, and a printer that does not answer in time a [PrinterError](crate::Error::PrinterError).
+    pub fn status(&self) -> Result<PrinterStatus, Error> {
+        if !self.printer_profile.supports_real_time {
+            return Err(Error::NoRealTimeCommands);
+        }
+        if !self.can_read() {
+            return Err(Error::UnsupportedForPrinterConnection);
+        }
+        let mut answers = [0u8; 4];
+        for (idx, answer) in answers.iter_mut().enumerate() {
+            let status = (idx + 1) as u8;
+            *answer = self.request_status(status)?.ok_or_else(|| Error::PrinterError(format!("no answer to the status request {}", status)))?;
+        }
+        Ok(PrinterStatus::from_bytes(answers[0], answers[1], answers[2], answers[3]))
+    }
+
+    /// Reads the status of the printer, as a one line summary
+    ///
+    /// Like "online, paper OK, cover closed", see [PrinterStatus](crate::PrinterStatus). The same errors as [status](Printer::status) apply.
+    pub fn status_string(&self) -> Result<String, Error> {
+        self.status().map(|status| status.to_string())
+    }
+
+    /// Blocks until the printer is online, with the cover closed and paper in it
+    ///
+    /// The [status](Printer::status) gets polled until the printer is [ready](crate::PrinterStatus::is_ready), or the timeout elapses, giving back a [Timeout](crate::Error::Timeout) error. Useful at startup, so the first print does not fail. The same errors as [status](Printer::status) apply, see [PrinterStatus::wait_online](crate::PrinterStatus::wait_online) for the polling details.
+    pub fn wait_online(&self, timeout: std::time::Duration) -> Result<(), Error> {
+        PrinterStatus::wait_online(|| self.status(), timeout).map(|_| ())
+    }
+
+    // Sends a status request, and gives back the answer if it arrives in time. Connections that cannot read give back None without sending anything.
+    fn request_status(&self, status: u8) -> Result<Option<u8>, Error> {
+        if !self.can_read() {
+            return Ok(None);
+        }
+        self.raw(Command::TransmitStatus{status}.as_bytes())?;
+        let mut answer = [0u8; 8];
+        match self.read(&mut answer)? {
+            0 => Ok(None),
+            count => Ok(Some(answer[count - 1]))
+        }
+    }
+
+    // If the connection can read the answers of the printer
+    fn can_read(&self) -> bool {
+        match &self.printer_connection {
+            PrinterConnection::Usb{in_endpoint, ..} => in_endpoint.is_some(),
+            PrinterConnection::Custom{transport} => transport.borrow().can_read(),
+            _other => false
+        }
+    }
+
+    /// Reads the bytes sent back by the printer, like the answer to a [TransmitStatus](crate::command::Command::TransmitStatus)
+    ///
+    /// Gives back the number of bytes read, which is 0 if nothing arrived before the profile's timeout. Only usb connections with a bulk in endpoint, and [transports](crate::Transport) that can read, are able to, other connections give an [UnsupportedForPrinterConnection](crate::Error::UnsupportedForPrinterConnection) error.
+    pub fn read(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        match &self.printer_connection {
+            PrinterConnection::Usb{in_endpoint: Some(in_endpoint), dh, timeout, ..} => {
+                match dh.read_bulk(*in_endpoint, buffer, *timeout) {
+                    Ok(count) => Ok(count),
+                    Err(rusb::Error::Timeout) => Ok(0),
+                    Err(e) => Err(Error::RusbError(e))
+                }
+            },
+            PrinterConnection::Custom{transport} if transport.borrow().can_read() => transport.borrow_mut().read(buffer),
+            _other => Err(Error::UnsupportedForPrinterConnection)
+        }
+    }
+
+    /// Prints a section header, with the label centered between fill characters.
+    ///
+    /// For more details, check [Formatter](crate::Formatter)'s [section](crate::Formatter::section).
+    pub fn section<A: AsRef<str>>(&self, label: A, fill: char) -> Result<(), Error> {
+        let content = self.formatter.section(label, fill);
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                self.terminal_print(&content);
+                Ok(())
+            },
+            _other => {
+                self.raw(self.code_table.encode(content)?)
+            }
+        }
+    }
+
+    /// Downloads a user-defined character into a code point.
+    ///
+    /// Useful for glyphs that the font lacks, like a currency symbol. The code must go from 32 to 126, otherwise an [InvalidUserCharCode](crate::Error::InvalidUserCharCode) error is returned. The character replaces the font's one only after sending [EnableUserChars](crate::command::Command::EnableUserChars). See [UserCharBitmap](crate::command::UserCharBitmap) for the cell size of each font.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::{Command, UserCharBitmap}};
+    /// let path = std::env::temp_dir().join("escpos-rs-define-user-char-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let bitmap = UserCharBitmap::new(3, vec![0xff; 36]);
+    /// printer.define_user_char(b'$', bitmap.clone())?;
+    /// assert_eq!(Command::DefineUserChar{code: b'$', bitmap: bitmap.clone()}.as_bytes(), std::fs::read(&path).unwrap());
+    /// assert!(printer.define_user_char(0x10, bitmap).is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn define_user_char(&self, code: u8, bitmap: UserCharBitmap) -> Result<(), Error> {
+        if !(32..=126).contains(&code) {
+            return Err(Error::InvalidUserCharCode(code));
+        }
+        self.raw(Command::DefineUserChar{code, bitmap}.as_bytes())
+    }
+
+    /// Prints a table with two columns.
+    ///
+    /// For more details, check [Formatter](crate::Formatter)'s [duo_table](crate::Formatter::duo_table).
+    pub fn duo_table<A: Into<String>, B: Into<String>, C: IntoIterator<Item = (D, E)>, D: Into<String>, E: Into<String>>(&self, headers: (A, B), rows: C) -> Result<(), Error> {
+        let cells = self.formatter.duo_table_cells(headers, rows);
+        self.print_table_cells(cells)
+    }
+
+    /// Prints a line with a label to the left and an amount to the right
+    ///
+    /// Like a single row of a [duo_table](Printer::duo_table), spanning the current width. For more details, check [Formatter](crate::Formatter)'s [line_item](crate::Formatter::line_item).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-line-item-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 20).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.line_item("Milk", "5.00")?;
+    /// assert_eq!(&b"Milk            5.00\n"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn line_item<A: Into<String>, B: Into<String>>(&self, label: A, amount: B) -> Result<(), Error> {
+        self.raw_str(self.formatter.line_item(label, amount))
+    }
+
+    /// Prints a total, as a bold and double height [line_item](Printer::line_item)
+    ///
+    /// The width of the characters does not change, so the line keeps the same columns. Bold and the character size get restored afterwards. In the terminal, the line gets printed without styling.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::{Command, Font}};
+    /// let path = std::env::temp_dir().join("escpos-rs-total-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_font_width(Font::FontA, 20).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.total("TOTAL", "12.50")?;
+    ///
+    /// let mut expected = Command::BoldOn.as_bytes();
+    /// expected.append(&mut Command::SelectCharacterSize{width: 1, height: 2}.as_bytes());
+    /// expected.extend_from_slice(b"TOTAL          12.50\n");
+    /// expected.append(&mut Command::SelectCharacterSize{width: 1, height: 1}.as_bytes());
+    /// expected.append(&mut Command::BoldOff.as_bytes());
+    /// assert_eq!(expected, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn total<A: Into<String>, B: Into<String>>(&self, label: A, amount: B) -> Result<(), Error> {
+        let content = self.formatter.line_item(label, amount);
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                self.terminal_print(&content);
+                Ok(())
+            },
+            _other => {
+                let (width, height) = self.text_size;
+                let mut feed = Command::BoldOn.as_bytes();
+                feed.append(&mut Command::SelectCharacterSize{width, height: (2 * height).min(8)}.as_bytes());
+                feed.append(&mut self.code_table.encode(content)?);
+                feed.append(&mut Command::SelectCharacterSize{width, height}.as_bytes());
+                feed.append(&mut Command::BoldOff.as_bytes());
+                self.raw(feed)
+            }
+        }
+    }
+
+    /// Prints a table with three columns.
+    ///
+    /// For more details, check [Formatter](crate::Formatter)'s [trio_table](crate::Formatter::trio_table).
+    pub fn trio_table<A: Into<String>, B: Into<String>, C: Into<String>, D: IntoIterator<Item = (E, F, G)>, E: Into<String>, F: Into<String>, G: Into<String>>(&self, headers: (A, B, C), rows: D) -> Result<(), Error> {
+        let cells = self.formatter.trio_table_cells(headers, rows);
+        self.print_table_cells(cells)
+    }
+
+    /// Prints a table following a column spec.
+    ///
+    /// For more details, check [Formatter](crate::Formatter)'s [table](crate::Formatter::table).
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, TableSpec, Justification};
+    ///
+    /// let path = std::env::temp_dir().join("escpos-rs-print-table-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let spec = TableSpec::new()
+    ///     .with_fixed_column("Qty", Justification::Right, 3)
+    ///     .with_weighted_column("Product", Justification::Left, 2)
+    ///     .with_weighted_column("Price", Justification::Right, 1);
+    /// printer.print_table(&spec, vec![vec!["2", "Milk", "10.00"]])?;
+    /// assert_eq!(printer.formatter().table(&spec, vec![vec!["2", "Milk", "10.00"]]).into_bytes(), std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_table<A: IntoIterator<Item = B>, B: IntoIterator<Item = C>, C: Into<String>>(&self, spec: &TableSpec, rows: A) -> Result<(), Error> {
+        let cells = self.formatter.table_cells(spec, rows);
+        self.print_table_cells(cells)
+    }
+
+    // Prints the cells of a table, encoding each column with its code table
+    fn print_table_cells(&self, cells: TableCells) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                self.terminal_print(&(self.formatter.render(cells) + "\n"));
+                Ok(())
+            },
+            _other => {
+                self.raw(self.formatter.encode_table(cells, &self.code_table)?)
+            }
+        }
+    }
+
+    /// Prints some text, followed by a centered QR code and a cut.
+    ///
+    /// Three lines are fed after the QR code, to reach the tear point. For more control over the layout, see [print_with_qr_options](Printer::print_with_qr_options).
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, Error, command::Font};
+    /// let path = std::env::temp_dir().join("escpos-rs-print-with-qr-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).build();
+    /// let printer = Printer::new(printer_profile.clone())?.unwrap();
+    /// printer.print_with_qr("Thanks!", "https://example.com/order/1")?;
+    ///
+    /// // The qr code gets printed as an image, as the profile has no native support
+    /// let footer = Instruction::text("Thanks!", Font::FontA, Justification::Left, None) +
+    ///     Instruction::vspace(1) +
+    ///     Instruction::qr_code("https://example.com/order/1".into())? +
+    ///     Instruction::vspace(3) +
+    ///     Instruction::cut();
+    /// assert_eq!(footer.to_vec(&printer_profile, None)?, std::fs::read(&path).unwrap());
+    ///
+    /// assert!(matches!(printer.print_with_qr("Thanks!", "x".repeat(8000)), Err(Error::QrError(_))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_with_qr<A: Into<String>, B: Into<String>>(&self, body: A, qr_content: B) -> Result<(), Error> {
+        self.print_with_qr_options(body, qr_content, Justification::Center, 3)
+    }
+
+    /// Prints some text, followed by a QR code with the given justification, `feed` empty lines and a cut.
+    ///
+    /// The body is printed with the current font. For more details, check [Instruction](crate::Instruction)'s [qr_footer](crate::Instruction::qr_footer).
+    pub fn print_with_qr_options<A: Into<String>, B: Into<String>>(&self, body: A, qr_content: B, justification: Justification, feed: u8) -> Result<(), Error> {
+        let instruction = Instruction::qr_footer(body, qr_content, self.font_and_width.0.clone(), justification, feed)?;
+        self.instruction(&instruction, None)
+    }
+
+    /// Prints a QR code, with the given error correction, module size (in dots) and justification.
+    ///
+    /// If the profile supports native QR codes, the printer generates the code by itself. Otherwise, the code gets printed as an image, trying to keep the requested module size.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Justification, command::{Command, ErrorCorrection}};
+    /// let path = std::env::temp_dir().join("escpos-rs-qr-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_native_qr(true).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.print_qr("hello", ErrorCorrection::L, 4, Justification::Right)?;
+    ///
+    /// let mut target = Command::SelectJustification{justification: Justification::Right}.as_bytes();
+    /// // Model 2, module size, error correction, storage and print
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x04, 0x00, 0x31, 0x41, 0x32, 0x00]);
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x43, 0x04]);
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x45, 0x30]);
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x08, 0x00, 0x31, 0x50, 0x30]);
+    /// target.extend_from_slice(b"hello");
+    /// target.extend_from_slice(&[0x1d, 0x28, 0x6b, 0x03, 0x00, 0x31, 0x51, 0x30]);
+    /// target.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_qr<A: Into<String>>(&self, content: A, error_correction: ErrorCorrection, size: u8, justification: Justification) -> Result<(), Error> {
+        self.print_qr_model(content, QrModel::Model2, error_correction, size, justification)
+    }
+
+    /// Prints a QR code of the given model, see [print_qr](Printer::print_qr).
+    ///
+    /// The model only applies to native QR codes. Codes printed as images are always of model 2.
+    /// ```rust,no_run
+    /// use escpos_rs::{Printer, PrinterModel, Justification, command::{ErrorCorrection, QrModel}};
+    /// let printer = Printer::new(PrinterModel::TMT20.usb_profile()).unwrap().unwrap();
+    /// // For a legacy scanner
+    /// printer.print_qr_model("ORDER-12", QrModel::Model1, ErrorCorrection::M, 6, Justification::Center)?;
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_qr_model<A: Into<String>>(&self, content: A, model: QrModel, error_correction: ErrorCorrection, size: u8, justification: Justification) -> Result<(), Error> {
+        let content = content.into();
+        if self.printer_profile.supports_native_qr {
+            let mut feed = Command::SelectJustification{justification}.as_bytes();
+            feed.append(&mut Command::QrCode{content, model, module_size: size, error_correction}.as_bytes());
+            feed.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+            self.raw(feed)
+        } else {
+            let ec_level = match error_correction {
+                ErrorCorrection::L => EcLevel::L,
+                ErrorCorrection::M => EcLevel::M,
+                ErrorCorrection::Q => EcLevel::Q,
+                ErrorCorrection::H => EcLevel::H
+            };
+            let code = QrCode::with_error_correction_level(content.as_bytes(), ec_level).map_err(Error::QrError)?;
+            // The module size gets reduced if the code does not fit in the paper
+            let printer_width = self.printer_profile.width as u32;
+            let modules = code.width() as u32;
+            let module_size = (size as u32).min(printer_width / modules).max(1);
+            let img = code.render::<image::Rgba<u8>>().quiet_zone(false).module_dimensions(module_size, module_size).build();
+            // Images span the whole width, so the code gets placed in a canvas as wide as the paper
+            let x_offset = match justification {
+                Justification::Left => 0,
+                Justification::Center => printer_width.saturating_sub(img.width()) / 2,
+                Justification::Right => printer_width.saturating_sub(img.width())
+            };
+            let mut canvas = image::DynamicImage::new_rgba8(printer_width, img.height());
+            image::imageops::overlay(&mut canvas, &img, x_offset, 0);
+            let escpos_image = EscposImage::new(canvas, 255, Justification::Left)?;
+            self.image(escpos_image)
+        }
+    }
+
+    /// Prints a centered QR code, or the content as centered text if it does not fit in one
+    ///
+    /// Handy for contents of unknown length, like user supplied urls. The code is printed as with [print_qr](Printer::print_qr) when the content fits in a model 2 code with the given error correction (see [QrModel::fits](crate::command::QrModel::fits)), and is not longer than `max_len` bytes (if given). Otherwise, the content gets printed as with [println_just](Printer::println_just). Gives back `true` if the QR code was printed.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, Justification, command::{Command, ErrorCorrection}};
+    /// let path = std::env::temp_dir().join("escpos-rs-qr-or-text-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_keep_open(false)?.with_native_qr(true).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    ///
+    /// // Short enough for a code
+    /// assert!(printer.print_qr_or_text("https://example.com/r/12", ErrorCorrection::M, 6, Some(64))?);
+    /// let center = Command::SelectJustification{justification: Justification::Center}.as_bytes();
+    /// assert!(std::fs::read(&path).unwrap().starts_with(&center));
+    /// # std::fs::remove_file(&path).unwrap();
+    ///
+    /// // Longer than the limit, printed as text
+    /// let url = "https://example.com/receipts/2021/12/24/1234";
+    /// assert!(!printer.print_qr_or_text(url, ErrorCorrection::M, 6, Some(32))?);
+    /// assert_eq!(printer.formatter().justify(url, Justification::Center).into_bytes(), std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn print_qr_or_text<A: Into<String>>(&self, content: A, error_correction: ErrorCorrection, size: u8, max_len: Option<usize>) -> Result<bool, Error> {
+        let content = content.into();
+        let within_limit = max_len.map(|max_len| content.len() <= max_len).unwrap_or(true);
+        if within_limit && QrModel::Model2.fits(&content, error_correction) {
+            self.print_qr(content, error_correction, size, Justification::Center)?;
+            Ok(true)
+        } else {
+            self.println_just(content, Justification::Center)?;
+            Ok(false)
+        }
+    }
+
+    pub fn image(&self, escpos_image: EscposImage) -> Result<(), Error> {
+        self.raw(escpos_image.feed_for_profile(&self.printer_profile, self.handler())?)
+    }
+
+    /// Prints an image as raster graphics, split in blocks of at most `max_rows` rows
+    ///
+    /// Useful for tall images, that would overflow the buffer of some printers when sent at once. See [EscposImage](crate::EscposImage)'s [feed_raster](crate::EscposImage::feed_raster).
+    pub fn image_raster(&self, escpos_image: &EscposImage, max_rows: u16) -> Result<(), Error> {
+        self.raw(escpos_image.feed_raster(self.printer_profile.width, max_rows))
+    }
+
+    /// Sends a string to the printer, encoded with the current code table
+    ///
+    /// Unlike [print](Printer::print), no line splitting takes place, so the content is sent exactly as given. Useful for preformatted content.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let path = std::env::temp_dir().join("escpos-rs-raw-str-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// // Sends exactly the bytes "Total:5.00", no newline is added
+    /// printer.raw_str("Total:5.00")?;
+    /// assert_eq!(&b"Total:5.00"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn raw_str<A: AsRef<str>>(&self, content: A) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Terminal => {
+                self.terminal_print(content.as_ref());
+                Ok(())
+            },
+            _other => self.raw(self.code_table.encode(content)?)
+        }
+    }
+
+    /// Sends raw information to the printer, as it gets produced
+    ///
+    /// Each chunk gets written as soon as the iterator yields it, so the whole content never needs to be in memory. Useful together with a streaming generator, like [instruction_streamed](Printer::instruction_streamed). Each chunk goes through [raw](Printer::raw), so large chunks still get split according to the profile's maximum transfer size. The first error stops the stream.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile};
+    /// let path = std::env::temp_dir().join("escpos-rs-raw-stream-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+    /// let chunks = vec![b"Milk ".to_vec(), b"5.00".to_vec(), b"\n".to_vec()];
+    /// printer.raw_stream(chunks.into_iter())?;
+    /// assert_eq!(&b"Milk 5.00\n"[..], std::fs::read(&path).unwrap().as_slice());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn raw_stream<I: Iterator<Item = Vec<u8>>>(&self, chunks: I) -> Result<(), Error> {
+        for chunk in chunks {
+            self.raw(&chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Sends raw information to the printer
+    ///
+    /// As simple as it sounds. Large buffers get sent in pieces of at most the profile's maximum transfer size, see [with_max_transfer_size](crate::PrinterProfileBuilder::with_max_transfer_size). Over usb, the whole write gets the profile's timeout once per piece, so a slow piece can use the time left over by the quick ones.
+    /// ```rust,no_run
+    /// use escpos_rs::{Printer,PrinterProfile};
+    /// let printer_profile = PrinterProfile::usb_builder(0x0001, 0x0001).build();
+    /// let printer = Printer::new(printer_profile).unwrap().unwrap();
+    /// printer.raw(&[0x01, 0x02])?;
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    ///
+    /// With a file connection, the bytes get appended to the file.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::Command};
+    /// let path = std::env::temp_dir().join("escpos-rs-raw-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_keep_open(false)?.build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.raw(Command::BoldOn.as_bytes())?;
+    /// printer.println("Total")?;
+    ///
+    /// let mut target = Command::BoldOn.as_bytes();
+    /// target.extend_from_slice(b"Total\n");
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn raw<A: AsRef<[u8]>>(&self, bytes: A) -> Result<(), Error> {
+        match &self.printer_connection {
+            PrinterConnection::Usb{endpoint, dh, timeout, ..} => {
+                // Each transfer gets the timeout, but the time left over by quick ones can be used by the slow ones
+                let transfers = bytes.as_ref().len().div_ceil(self.printer_profile.max_transfer_size.max(1)) as u32;
+                let deadline = std::time::Instant::now() + *timeout * transfers;
+                self.write_chunks(bytes.as_ref(), |chunk| {
+                    let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+                    if remaining.is_zero() {
+                        return Err(Error::RusbError(rusb::Error::Timeout));
+                    }
+                    dh.write_bulk(*endpoint, chunk, remaining).map_err(Error::RusbError)
+                })?;
+                self.write_tee(bytes.as_ref())?;
+                self.write_mirror(bytes.as_ref())
+            },
+            PrinterConnection::Custom{transport} => {
+                self.write_chunks(bytes.as_ref(), |chunk| transport.borrow_mut().write(chunk))?;
+                self.write_tee(bytes.as_ref())?;
+                self.write_mirror(bytes.as_ref())
+            },
+            PrinterConnection::File{path, file} => {
+                if let Some(mut file) = file.as_ref() {
+                    file.write_all(bytes.as_ref()).map_err(Error::IoError)?;
+                } else {
+                    open_append(path)?.write_all(bytes.as_ref()).map_err(Error::IoError)?;
+                }
+                self.record_write(bytes.as_ref().len());
+                self.write_tee(bytes.as_ref())?;
+                self.write_mirror(bytes.as_ref())
+            },
+            _other => panic!("Unimplemented")
+        }
+    }
+
+    // Writes the bytes in pieces of at most the maximum transfer size, as the device might accept only part of each one
+    fn write_chunks<F: FnMut(&[u8]) -> Result<usize, Error>>(&self, bytes: &[u8], mut write: F) -> Result<(), Error> {
+        let max_transfer_size = self.printer_profile.max_transfer_size.max(1);
+        for chunk in bytes.chunks(max_transfer_size) {
+            let mut written = 0;
+            while written < chunk.len() {
+                let count = write(&chunk[written..])?;
+                self.record_write(count);
+                if count == 0 {
+                    return Err(Error::PrinterError("the device did not accept any bytes".to_string()));
+                }
+                written += count;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Auxiliar function to open a file connection, without truncating it
+fn open_append(path: &std::path::Path) -> Result<std::fs::File, Error> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(Error::IoError)
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use super::Printer;
+use crate::{Instruction, PrintData, Error};
+use std::sync::mpsc::{channel, Sender, Receiver, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Times a job gets retried after a transient usb error
+const RETRIES: u32 = 3;
+/// Time to wait before retrying a job
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Job waiting in the queue, and where to report its result
+struct QueuedJob {
+    instruction: Instruction,
+    print_data: Option<PrintData>,
+    result: Sender<Result<(), Error>>
+}
+
+/// Prints in a background thread, so the caller does not block
+///
+/// Created with the [Printer](crate::Printer)'s [into_queue](crate::Printer::into_queue) method, which moves the printer to a thread of its own. Jobs get printed one at a time, in the order they were queued, and each one gives back a [PrintJob](crate::PrintJob) to check how it went. Transient usb errors (timeouts, busy or stalled endpoints) get retried a few times before the job fails, as long as no byte of the job reached the printer; a job that failed half way is reported right away, so its first part does not get printed twice.
+///
+/// ```rust
+/// use escpos_rs::{Printer, PrinterProfile, Instruction, Justification, command::Font};
+/// let path = std::env::temp_dir().join("escpos-rs-print-queue-example.bin");
+/// # let _ = std::fs::remove_file(&path);
+/// let printer = Printer::new(PrinterProfile::file_builder(&path).build())?.unwrap();
+/// let queue = printer.into_queue();
+///
+/// let mut job = queue.print_async_bg(Instruction::text("Order 42", Font::FontA, Justification::Left, None), None);
+/// // The caller is free to do something else, and check later
+/// while !job.is_done() {
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+/// }
+/// job.wait()?;
+///
+/// // The printer can be taken back, once the queue is empty
+/// let _printer = queue.into_printer();
+/// assert!(std::fs::read(&path).unwrap().ends_with(b"Order 42\n"));
+/// # std::fs::remove_file(&path).unwrap();
+/// # Ok::<(), escpos_rs::Error>(())
+/// ```
+pub struct PrintQueue {
+    sender: Sender<QueuedJob>,
+    worker: JoinHandle<Printer>
+}
+
+impl PrintQueue {
+    pub(crate) fn new(printer: Printer) -> PrintQueue {
+        let (sender, receiver) = channel::<QueuedJob>();
+        let worker = std::thread::spawn(move || {
+            for job in receiver {
+                let result = print_with_retries(&printer, &job);
+                // The caller might have dropped the job, nobody to tell then
+                let _ = job.result.send(result);
+            }
+            printer
+        });
+        PrintQueue {
+            sender,
+            worker
+        }
+    }
+
+    /// Queues an instruction to be printed, and returns right away
+    pub fn print_async_bg(&self, instruction: Instruction, print_data: Option<PrintData>) -> PrintJob {
+        let (result, receiver) = channel();
+        let job = QueuedJob {
+            instruction,
+            print_data,
+            result
+        };
+        // If the worker is gone, the job reports it as soon as it gets checked
+        let _ = self.sender.send(job);
+        PrintJob {
+            receiver,
+            result: None
+        }
+    }
+
+    /// Waits for the queued jobs to finish, and gives back the printer
+    pub fn into_printer(self) -> Printer {
+        drop(self.sender);
+        match self.worker.join() {
+            Ok(printer) => printer,
+            Err(e) => std::panic::resume_unwind(e)
+        }
+    }
+}
+
+/// Handle to a job queued in a [PrintQueue](crate::PrintQueue)
+pub struct PrintJob {
+    receiver: Receiver<Result<(), Error>>,
+    /// Result of the job, once it arrived
+    result: Option<Result<(), Error>>
+}
+
+impl PrintJob {
+    /// Checks, without blocking, if the job finished (successfully or not)
+    pub fn is_done(&mut self) -> bool {
+        if self.result.is_none() {
+            self.result = match self.receiver.try_recv() {
+                Ok(result) => Some(result),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Err(queue_stopped()))
+            };
+        }
+        self.result.is_some()
+    }
+
+    /// Blocks until the job finishes, and gives back its result
+    pub fn wait(self) -> Result<(), Error> {
+        match self.result {
+            Some(result) => result,
+            None => self.receiver.recv().unwrap_or_else(|_| Err(queue_stopped()))
+        }
+    }
+}
+
+// Prints a job, retrying if the usb connection had a hiccup before anything got through
+fn print_with_retries(printer: &Printer, job: &QueuedJob) -> Result<(), Error> {
+    let mut attempt = 0;
+    loop {
+        let before = printer.stats().bytes_written;
+        let result = printer.instruction(&job.instruction, job.print_data.as_ref());
+        // Once part of the job reached the printer, printing it again would duplicate that part
+        let nothing_written = printer.stats().bytes_written == before;
+        match result {
+            Err(Error::RusbError(rusb::Error::Timeout)) |
+            Err(Error::RusbError(rusb::Error::Busy)) |
+            Err(Error::RusbError(rusb::Error::Interrupted)) |
+            Err(Error::RusbError(rusb::Error::Pipe)) if nothing_written && attempt < RETRIES => {
+                attempt += 1;
+                std::thread::sleep(RETRY_DELAY);
+            },
+            result => return result
+        }
+    }
+}
+
+// Error for jobs whose queue stopped before they finished
+fn queue_stopped() -> Error {
+    Error::PrinterError("the print queue stopped before finishing the job".to_string())
+}
@@ -0,0 +1,10 @@
+/// Counters of the data sent to a printer
+///
+/// Obtained through the [Printer](crate::Printer)'s [stats](crate::Printer::stats) method. The counters are cumulative since the printer was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PrintStats {
+    /// Total number of bytes sent
+    pub bytes_written: u64,
+    /// Number of writes (usb transfers, file writes or terminal prints) made to send those bytes
+    pub write_calls: u64
+}
@@ -1,26 +1,88 @@
-use super::{PrinterProfile};
-use crate::{PrinterConnectionData, command::Font};
+use super::{PrinterProfile, PrinterProfileBuilder};
+use std::collections::HashMap;
+use crate::{PrinterConnectionData, ImageMode, command::{Command, CodeTable, Font}};
 
 /// Printers known to this library
 ///
 /// Probably needs updates. If you know one that is not in the list, send them to the author through email to be considered in future updates.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PrinterModel {
     /// ZKTeco mini printer
     ZKTeco,
     /// Epson most used printer
-    TMT20
+    TMT20,
+    /// Unbranded 80mm printer, as sold under many names (POS-80 and similar)
+    ///
+    /// Fits 48 columns with font A, and 64 in condensed mode (font B).
+    /// ```rust
+    /// use escpos_rs::{PrinterModel, command::Font};
+    /// let printer_profile = PrinterModel::Generic80mm.usb_profile();
+    /// assert_eq!(576, printer_profile.width());
+    /// assert_eq!(Some(48), printer_profile.columns_for(&Font::FontA));
+    /// assert_eq!(Some(64), printer_profile.columns_for(&Font::FontB));
+    /// ```
+    Generic80mm,
+    /// Unbranded 58mm printer, as sold under many names (POS-58 and similar)
+    ///
+    /// Fits 32 columns with font A, and 42 in condensed mode (font B). Most of them come without a cutter.
+    Generic58mm
 }
 
 impl PrinterModel {
+    /// All the models known to this library
+    pub const ALL: &'static [PrinterModel] = &[PrinterModel::ZKTeco, PrinterModel::TMT20, PrinterModel::Generic80mm, PrinterModel::Generic58mm];
+
+    /// Finds the known model with the given vendor and product id
+    ///
+    /// ```rust
+    /// use escpos_rs::PrinterModel;
+    /// assert_eq!(Some(PrinterModel::TMT20), PrinterModel::from_vid_pid(0x04b8, 0x0e15));
+    /// assert_eq!(None, PrinterModel::from_vid_pid(0x0001, 0x0001));
+    /// ```
+    pub fn from_vid_pid(vendor_id: u16, product_id: u16) -> Option<PrinterModel> {
+        PrinterModel::ALL.iter().find(|model| {
+            let (model_vendor_id, model_product_id, _) = model.vp_id();
+            model_vendor_id == vendor_id && model_product_id == product_id
+        }).copied()
+    }
+
     /// Get the vendor, product id and endpoint of the current model
     pub fn vp_id(&self) -> (u16, u16, Option<u8>) {
         match self {
             PrinterModel::ZKTeco => (0x6868, 0x0200, Some(0x02)),
-            PrinterModel::TMT20 => (0x04b8, 0x0e15, Some(0x01))
+            PrinterModel::TMT20 => (0x04b8, 0x0e15, Some(0x01)),
+            // The endpoint varies between clones, so it gets detected
+            PrinterModel::Generic80mm => (0x0416, 0x5011, None),
+            PrinterModel::Generic58mm => (0x0fe6, 0x811e, None)
         }
     }
 
+    /// Obtain a builder pre-populated with the model's usb profile
+    ///
+    /// Useful to keep the model's presets while overriding some of them.
+    /// ```rust
+    /// use escpos_rs::{PrinterModel, command::Font};
+    /// let printer_profile = PrinterModel::ZKTeco.profile_builder()
+    ///     .with_width(576)
+    ///     .with_font_width(Font::FontA, 48)
+    ///     .build();
+    /// // Without overrides, the builder reproduces the model's profile
+    /// assert_eq!(PrinterModel::TMT20.usb_profile(), PrinterModel::TMT20.profile_builder().build());
+    /// ```
+    pub fn profile_builder(&self) -> PrinterProfileBuilder {
+        PrinterProfileBuilder::from_profile(self.usb_profile())
+    }
+
     /// Obtain the details to connect to a printer model through usb
+    ///
+    /// Each model comes with its own presets, like the cut command it understands.
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterModel, command::Command};
+    /// let cut = Instruction::cut();
+    /// assert_eq!(Command::Cut.as_bytes(), cut.to_vec(&PrinterModel::ZKTeco.usb_profile(), None)?);
+    /// assert_eq!(vec![0x1d, 0x56, 0x42, 0x00], cut.to_vec(&PrinterModel::TMT20.usb_profile(), None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
     pub fn usb_profile(&self) -> PrinterProfile {
         let (vendor_id, product_id, endpoint) = self.vp_id();
         match self {
@@ -30,10 +92,25 @@ impl PrinterModel {
                         vendor_id,
                         product_id,
                         endpoint,
-                        timeout: std::time::Duration::from_secs(2)
+                        timeout: std::time::Duration::from_secs(2),
+                        keep_kernel_driver: false
                     },
                     columns_per_font: vec![(Font::FontA, 32), (Font::FontB, 42)].into_iter().collect(),
-                    width: 384
+                    width: 384,
+                    default_code_table: CodeTable::USA,
+                    supports_native_qr: false,
+                    max_transfer_size: 4096,
+                    has_cutter: true,
+                    cut_command: Command::Cut.as_bytes(),
+                    supported_image_modes: vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble],
+                    font_select_bytes: HashMap::new(),
+                    auto_init: false,
+                    has_black_mark_sensor: false,
+                    supports_real_time: false,
+                    has_presenter: false,
+                    supports_color: false,
+                    sync_before_cut: false,
+                    terminal_writer: None
                 }
             },
             PrinterModel::TMT20 => {
@@ -42,10 +119,79 @@ impl PrinterModel {
                         vendor_id,
                         product_id,
                         endpoint,
-                        timeout: std::time::Duration::from_secs(2)
+                        timeout: std::time::Duration::from_secs(2),
+                        keep_kernel_driver: false
                     },
                     columns_per_font: vec![(Font::FontA, 48)].into_iter().collect(),
-                    width: 576
+                    width: 576,
+                    default_code_table: CodeTable::USA,
+                    supports_native_qr: true,
+                    max_transfer_size: 4096,
+                    has_cutter: true,
+                    cut_command: vec![0x1d, 0x56, 0x42, 0x00],
+                    supported_image_modes: vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble, ImageMode::TwentyFourDotSingle, ImageMode::TwentyFourDotDouble],
+                    font_select_bytes: HashMap::new(),
+                    auto_init: false,
+                    has_black_mark_sensor: false,
+                    supports_real_time: true,
+                    has_presenter: false,
+                    supports_color: false,
+                    sync_before_cut: false,
+                    terminal_writer: None
+                }
+            },
+            PrinterModel::Generic80mm => {
+                PrinterProfile {
+                    printer_connection_data: PrinterConnectionData::Usb {
+                        vendor_id,
+                        product_id,
+                        endpoint,
+                        timeout: std::time::Duration::from_secs(2),
+                        keep_kernel_driver: false
+                    },
+                    columns_per_font: vec![(Font::FontA, 48), (Font::FontB, 64)].into_iter().collect(),
+                    width: 576,
+                    default_code_table: CodeTable::USA,
+                    supports_native_qr: true,
+                    max_transfer_size: 4096,
+                    has_cutter: true,
+                    cut_command: vec![0x1d, 0x56, 0x01],
+                    supported_image_modes: vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble, ImageMode::TwentyFourDotSingle, ImageMode::TwentyFourDotDouble],
+                    font_select_bytes: HashMap::new(),
+                    auto_init: false,
+                    has_black_mark_sensor: false,
+                    supports_real_time: false,
+                    has_presenter: false,
+                    supports_color: false,
+                    sync_before_cut: false,
+                    terminal_writer: None
+                }
+            },
+            PrinterModel::Generic58mm => {
+                PrinterProfile {
+                    printer_connection_data: PrinterConnectionData::Usb {
+                        vendor_id,
+                        product_id,
+                        endpoint,
+                        timeout: std::time::Duration::from_secs(2),
+                        keep_kernel_driver: false
+                    },
+                    columns_per_font: vec![(Font::FontA, 32), (Font::FontB, 42)].into_iter().collect(),
+                    width: 384,
+                    default_code_table: CodeTable::USA,
+                    supports_native_qr: false,
+                    max_transfer_size: 4096,
+                    has_cutter: false,
+                    cut_command: Command::Cut.as_bytes(),
+                    supported_image_modes: vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble],
+                    font_select_bytes: HashMap::new(),
+                    auto_init: false,
+                    has_black_mark_sensor: false,
+                    supports_real_time: false,
+                    has_presenter: false,
+                    supports_color: false,
+                    sync_before_cut: false,
+                    terminal_writer: None
                 }
             }
         }
@@ -1,13 +1,39 @@
+extern crate serde;
+
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
 use crate::{
-    Error,
-    command::Font
+    Error, ImageMode,
+    command::{Command, CodeTable, Font}
 };
 
+/// Lines fed instead of a cut, for printers without a cutter
+const CUTLESS_FEED: u8 = 4;
+
+/// Writer that receives the output of a terminal printer
+///
+/// Shared, so profiles can still be cloned. Two writers are only equal if they are the same one.
+#[derive(Clone)]
+pub(crate) struct TerminalWriter(pub(crate) Arc<Mutex<Box<dyn std::io::Write + Send>>>);
+
+impl std::fmt::Debug for TerminalWriter {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "TerminalWriter")
+    }
+}
+
+impl PartialEq for TerminalWriter {
+    fn eq(&self, other: &TerminalWriter) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 /// Available connections with the printer
 ///
-/// Determines the kind of connection that will be sustained with the printer. At the moment, only Usb and Terminal are implemented. Try not to use this enum directly, use the builder pattern instead (using the [usb_builder](PrinterProfile::usb_builder) or [usb_builder](PrinterProfile::terminal_builder) methods. `network_builder` soon to be available).
-#[derive(Clone, Debug)]
+/// Determines the kind of connection that will be sustained with the printer. At the moment, only Usb, File and Terminal are implemented. Try not to use this enum directly, use the builder pattern instead (using the [usb_builder](PrinterProfile::usb_builder), [file_builder](PrinterProfile::file_builder) or [terminal_builder](PrinterProfile::terminal_builder) methods. `network_builder` soon to be available).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
 pub enum PrinterConnectionData {
     /// Usb connection
     Usb {
@@ -16,9 +42,22 @@ pub enum PrinterConnectionData {
         /// product id for the printer
         product_id: u16,
         /// Endpoint where the usb data is meant to be written to
+        #[serde(default)]
         endpoint: Option<u8>,
         /// Timeout for bulk write operations
-        timeout: std::time::Duration
+        #[serde(rename = "timeout_ms", with = "duration_millis", default = "default_timeout")]
+        timeout: std::time::Duration,
+        /// If the `usblp` kernel driver should be kept, writing to its character device when available
+        #[serde(default)]
+        keep_kernel_driver: bool
+    },
+    /// File or device connection, like `/dev/usb/lp0` or a file read by a raw CUPS queue
+    File {
+        /// Path to write the data to
+        path: std::path::PathBuf,
+        /// If the file is opened once and kept open, or opened (in append mode) for each write
+        #[serde(default = "default_true")]
+        keep_open: bool
     },
     /// Network connection (not implemented yet)
     Network {
@@ -29,17 +68,98 @@ pub enum PrinterConnectionData {
     Terminal
 }
 
+/// Compact summary of the connection, for logs
+///
+/// ```rust
+/// use escpos_rs::PrinterConnectionData;
+/// let connection = PrinterConnectionData::File{path: "/dev/usb/lp0".into(), keep_open: true};
+/// assert_eq!("File /dev/usb/lp0", connection.to_string());
+/// ```
+impl std::fmt::Display for PrinterConnectionData {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            PrinterConnectionData::Usb{vendor_id, product_id, endpoint, ..} => {
+                write!(formatter, "USB {:04x}:{:04x} ep=", vendor_id, product_id)?;
+                match endpoint {
+                    Some(endpoint) => write!(formatter, "{:#04x}", endpoint),
+                    None => write!(formatter, "auto")
+                }
+            },
+            PrinterConnectionData::File{path, ..} => write!(formatter, "File {}", path.display()),
+            PrinterConnectionData::Network{_host, _port} => write!(formatter, "Network {}:{}", _host, _port),
+            PrinterConnectionData::Terminal => write!(formatter, "Terminal")
+        }
+    }
+}
+
 /// Details required to connect and print
 ///
 /// In order to use the full functionality of the library, some information should be provided regarding the printer. The bare minimum information needed is the product id and the vendor id.
-#[derive(Clone, Debug)]
+///
+/// Profiles can be stored, for example to select the printer through a configuration file. In the serialized form, the usb timeout is given in milliseconds, and everything but the connection, the fonts and the width can be left out to use the defaults of the builders.
+///
+/// ```rust
+/// use escpos_rs::{PrinterProfile, command::Font};
+///
+/// let printer_profile = PrinterProfile::usb_builder(0x04b8, 0x0e15)
+///     .with_font_width(Font::FontB, 64)
+///     .with_timeout(std::time::Duration::from_millis(1500)).unwrap()
+///     .build();
+/// let serialized = serde_json::to_string(&printer_profile).unwrap();
+/// assert!(serialized.contains("\"timeout_ms\":1500"));
+/// let deserialized: PrinterProfile = serde_json::from_str(&serialized).unwrap();
+/// assert_eq!(printer_profile, deserialized);
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct PrinterProfile {
     /// Existing connection to the printer
     pub (crate) printer_connection_data: PrinterConnectionData,
     /// Paper width, in characters, for the printer
     pub (crate) columns_per_font: HashMap<Font, u8>,
     /// Total printer width in pixels, for image printing
-    pub (crate) width: u16
+    pub (crate) width: u16,
+    /// Code table the printer uses when powered on
+    #[serde(default = "default_code_table")]
+    pub (crate) default_code_table: CodeTable,
+    /// If the printer can generate QR codes by itself
+    #[serde(default)]
+    pub (crate) supports_native_qr: bool,
+    /// Maximum number of bytes to send in a single transfer
+    #[serde(default = "default_max_transfer_size")]
+    pub (crate) max_transfer_size: usize,
+    /// If the printer has an autocutter
+    #[serde(default = "default_true")]
+    pub (crate) has_cutter: bool,
+    /// Bytes sent to cut the paper
+    #[serde(default = "default_cut_command")]
+    pub (crate) cut_command: Vec<u8>,
+    /// Bit image modes the printer understands
+    #[serde(default = "default_image_modes")]
+    pub (crate) supported_image_modes: Vec<ImageMode>,
+    /// Bytes sent to select a font, instead of the standard `ESC M n`
+    #[serde(default)]
+    pub (crate) font_select_bytes: HashMap<Font, Vec<u8>>,
+    /// If each print starts by initializing the printer
+    #[serde(default)]
+    pub (crate) auto_init: bool,
+    /// If the printer can detect black marks on the paper
+    #[serde(default)]
+    pub (crate) has_black_mark_sensor: bool,
+    /// If the printer processes real-time commands (DLE)
+    #[serde(default)]
+    pub (crate) supports_real_time: bool,
+    /// If the printer has a presenter (or peeler) for the printed paper
+    #[serde(default)]
+    pub (crate) has_presenter: bool,
+    /// If the printer prints in two colors
+    #[serde(default)]
+    pub (crate) supports_color: bool,
+    /// If cuts wait for the printer to confirm it consumed the previous data
+    #[serde(default)]
+    pub (crate) sync_before_cut: bool,
+    /// Where a terminal printer writes, instead of the standard output
+    #[serde(skip)]
+    pub (crate) terminal_writer: Option<TerminalWriter>
 }
 
 impl PrinterProfile {
@@ -50,7 +170,21 @@ impl PrinterProfile {
         PrinterProfile {
             printer_connection_data,
             columns_per_font,
-            width
+            width,
+            default_code_table: CodeTable::USA,
+            supports_native_qr: false,
+            max_transfer_size: 4096,
+            has_cutter: true,
+            cut_command: Command::Cut.as_bytes(),
+            supported_image_modes: default_image_modes(),
+            font_select_bytes: HashMap::new(),
+            auto_init: false,
+            has_black_mark_sensor: false,
+            supports_real_time: false,
+            has_presenter: false,
+            supports_color: false,
+            sync_before_cut: false,
+            terminal_writer: None
         }
     }
 
@@ -66,6 +200,17 @@ impl PrinterProfile {
         PrinterProfileBuilder::new_usb(vendor_id, product_id)
     }
 
+    /// Creates a [PrinterProfileBuilder](crate::PrinterProfileBuilder) set for printing to a file or device path
+    ///
+    /// Equivalent to a call to [PrinterProfileBuilder](crate::PrinterProfileBuilder)'s [new_file](crate::PrinterProfileBuilder::new_file) function.
+    /// ```rust
+    /// use escpos_rs::PrinterProfile;
+    /// let printer_profile = PrinterProfile::file_builder("/dev/usb/lp0").build();
+    /// ```
+    pub fn file_builder<A: Into<std::path::PathBuf>>(path: A) -> PrinterProfileBuilder {
+        PrinterProfileBuilder::new_file(path)
+    }
+
     /// Creates a [PrinterProfileBuilder](crate::PrinterProfileBuilder) set for terminal printing
     ///
     /// Equivalent to a call to [PrinterProfileBuilder](crate::PrinterProfileBuilder)'s [new_terminal](crate::PrinterProfileBuilder::new_terminal) function.
@@ -77,6 +222,124 @@ impl PrinterProfile {
     pub fn terminal_builder() -> PrinterProfileBuilder {
         PrinterProfileBuilder::new_terminal()
     }
+
+    /// Creates a [PrinterProfileBuilder](crate::PrinterProfileBuilder) set for terminal printing, to the given writer.
+    ///
+    /// Equivalent to a call to [PrinterProfileBuilder](crate::PrinterProfileBuilder)'s [new_terminal_with](crate::PrinterProfileBuilder::new_terminal_with) function.
+    pub fn terminal_builder_with(writer: Box<dyn std::io::Write + Send>) -> PrinterProfileBuilder {
+        PrinterProfileBuilder::new_terminal_with(writer)
+    }
+
+    /// Loads a profile from its json representation
+    ///
+    /// Allows selecting the printer with a configuration file, without recompiling. Requires the `json` feature. See [PrinterProfile](crate::PrinterProfile) for the defaults of the fields that can be left out.
+    ///
+    /// ```rust
+    /// use escpos_rs::{PrinterProfile, command::Font};
+    /// let printer_profile = PrinterProfile::from_json(r#"{
+    ///     "printer_connection_data": {"kind": "Usb", "vendor_id": 1208, "product_id": 3605, "timeout_ms": 1500},
+    ///     "columns_per_font": {"FontA": 48, "FontB": 64},
+    ///     "width": 576
+    /// }"#)?;
+    /// let expected = PrinterProfile::usb_builder(0x04b8, 0x0e15)
+    ///     .with_width(576)
+    ///     .with_font_width(Font::FontA, 48)
+    ///     .with_font_width(Font::FontB, 64)
+    ///     .with_timeout(std::time::Duration::from_millis(1500))?
+    ///     .build();
+    /// assert_eq!(expected, printer_profile);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<PrinterProfile, Error> {
+        serde_json::from_str(json).map_err(|e| Error::JsonError(e.to_string()))
+    }
+
+    /// Width of the paper, in dots
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Columns that fit in a line with the given font, if the font is known for this profile
+    pub fn columns_for(&self, font: &Font) -> Option<u8> {
+        self.columns_per_font.get(font).copied()
+    }
+
+    /// Checks if a usb device, given the vendor and product id of its descriptor, is a printer of this profile
+    ///
+    /// Used to pick the devices to connect to, see the [Printer](crate::Printer)'s [new](crate::Printer::new) and [new_all](crate::Printer::new_all). Profiles for other connections match no usb device.
+    ///
+    /// ```rust
+    /// use escpos_rs::PrinterProfile;
+    ///
+    /// let printer_profile = PrinterProfile::usb_builder(0x04b8, 0x0e15).build();
+    /// // Descriptors of the connected devices: two identical printers, a different printer and a keyboard
+    /// let devices = vec![(0x04b8, 0x0e15), (0x0416, 0x5011), (0x046d, 0xc31c), (0x04b8, 0x0e15)];
+    /// let matching: Vec<usize> = devices.iter().enumerate()
+    ///     .filter(|(_, (vendor_id, product_id))| printer_profile.matches_usb_device(*vendor_id, *product_id))
+    ///     .map(|(idx, _)| idx)
+    ///     .collect();
+    /// assert_eq!(vec![0, 3], matching);
+    ///
+    /// // Same vendor, another product
+    /// assert!(!printer_profile.matches_usb_device(0x04b8, 0x0202));
+    /// assert!(!PrinterProfile::terminal_builder().build().matches_usb_device(0x04b8, 0x0e15));
+    /// ```
+    pub fn matches_usb_device(&self, vendor_id: u16, product_id: u16) -> bool {
+        match &self.printer_connection_data {
+            PrinterConnectionData::Usb{vendor_id: profile_vendor_id, product_id: profile_product_id, ..} => *profile_vendor_id == vendor_id && *profile_product_id == product_id,
+            _ => false
+        }
+    }
+
+    /// Bytes that finish a receipt, a cut or some lines to reach the tear bar if there is no cutter
+    pub(crate) fn cut_bytes(&self) -> Vec<u8> {
+        if self.has_cutter {
+            self.cut_command.clone()
+        } else {
+            Command::FeedLines{lines: CUTLESS_FEED}.as_bytes()
+        }
+    }
+
+    /// Bytes that select a font, the profile's own command if it has one
+    pub(crate) fn font_bytes(&self, font: &Font) -> Vec<u8> {
+        match self.font_select_bytes.get(font) {
+            Some(bytes) => bytes.clone(),
+            None => Command::SelectFont{font: font.clone()}.as_bytes()
+        }
+    }
+
+    /// Bytes that start a print, the initialization if auto init is enabled
+    ///
+    /// The initialization brings the printer back to the usual code table, so the one the text gets encoded with is selected again.
+    pub(crate) fn init_bytes(&self, code_table: &CodeTable) -> Vec<u8> {
+        if self.auto_init {
+            let mut bytes = Command::Init.as_bytes();
+            if *code_table != CodeTable::USA {
+                bytes.append(&mut Command::SelectCodeTable{code_table: code_table.clone()}.as_bytes());
+            }
+            bytes
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Compact summary of the profile, for logs
+///
+/// Gives the connection, the width in dots and the columns of each font.
+///
+/// ```rust
+/// use escpos_rs::PrinterModel;
+/// assert_eq!("USB 04b8:0e15 ep=0x01 width=576 fonts=[FontA:48]", PrinterModel::TMT20.usb_profile().to_string());
+/// ```
+impl std::fmt::Display for PrinterProfile {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        // Sorted, so the summary does not depend on the map's order
+        let mut fonts: Vec<String> = self.columns_per_font.iter().map(|(font, columns)| format!("{:?}:{}", font, columns)).collect();
+        fonts.sort();
+        write!(formatter, "{} width={} fonts=[{}]", self.printer_connection_data, self.width, fonts.join(", "))
+    }
 }
 
 /// Helper structure to create a [PrinterProfile](crate::PrinterProfile)
@@ -88,7 +351,33 @@ pub struct PrinterProfileBuilder {
     /// Columns that each font spans at maximum
     columns_per_font: HashMap<Font, u8>,
     /// Widtth, in dots, of the printer
-    width: u16
+    width: u16,
+    /// Code table of the printer when powered on
+    default_code_table: CodeTable,
+    /// If the printer can generate QR codes by itself
+    supports_native_qr: bool,
+    /// Maximum number of bytes per transfer
+    max_transfer_size: usize,
+    /// If the printer has an autocutter
+    has_cutter: bool,
+    /// Bytes sent to cut the paper
+    cut_command: Vec<u8>,
+    supported_image_modes: Vec<ImageMode>,
+    font_select_bytes: HashMap<Font, Vec<u8>>,
+    /// If each print starts by initializing the printer
+    auto_init: bool,
+    /// If the printer can detect black marks on the paper
+    has_black_mark_sensor: bool,
+    /// If the printer processes real-time commands (DLE)
+    supports_real_time: bool,
+    /// If the printer has a presenter (or peeler) for the printed paper
+    has_presenter: bool,
+    /// If the printer prints in two colors
+    supports_color: bool,
+    /// If cuts wait for the printer to confirm it consumed the previous data
+    sync_before_cut: bool,
+    /// Where a terminal printer writes, instead of the standard output
+    terminal_writer: Option<TerminalWriter>
 }
 
 impl PrinterProfileBuilder {
@@ -109,10 +398,81 @@ impl PrinterProfileBuilder {
                 vendor_id,
                 product_id,
                 endpoint: None,
-                timeout: std::time::Duration::from_secs(2)
+                timeout: std::time::Duration::from_secs(2),
+                keep_kernel_driver: false
             },
             columns_per_font: vec![(Font::FontA, 32)].into_iter().collect(),
-            width: 384
+            width: 384,
+            default_code_table: CodeTable::USA,
+            supports_native_qr: false,
+            max_transfer_size: 4096,
+            has_cutter: true,
+            cut_command: Command::Cut.as_bytes(),
+            supported_image_modes: default_image_modes(),
+            font_select_bytes: HashMap::new(),
+            auto_init: false,
+            has_black_mark_sensor: false,
+            supports_real_time: false,
+            has_presenter: false,
+            supports_color: false,
+            sync_before_cut: false,
+            terminal_writer: None
+        }
+    }
+
+    /// Creates a builder pre-populated with the contents of an existing profile
+    pub(crate) fn from_profile(printer_profile: PrinterProfile) -> PrinterProfileBuilder {
+        PrinterProfileBuilder {
+            printer_connection_data: printer_profile.printer_connection_data,
+            columns_per_font: printer_profile.columns_per_font,
+            width: printer_profile.width,
+            default_code_table: printer_profile.default_code_table,
+            supports_native_qr: printer_profile.supports_native_qr,
+            max_transfer_size: printer_profile.max_transfer_size,
+            has_cutter: printer_profile.has_cutter,
+            cut_command: printer_profile.cut_command,
+            supported_image_modes: printer_profile.supported_image_modes,
+            font_select_bytes: printer_profile.font_select_bytes,
+            auto_init: printer_profile.auto_init,
+            has_black_mark_sensor: printer_profile.has_black_mark_sensor,
+            supports_real_time: printer_profile.supports_real_time,
+            has_presenter: printer_profile.has_presenter,
+            supports_color: printer_profile.supports_color,
+            sync_before_cut: printer_profile.sync_before_cut,
+            terminal_writer: printer_profile.terminal_writer
+        }
+    }
+
+    /// Creates a new [PrinterProfileBuilder](crate::PrinterProfileBuilder) set for printing to a file or device path
+    ///
+    /// ```rust
+    /// use escpos_rs::PrinterProfileBuilder;
+    /// let printer_profile_builder = PrinterProfileBuilder::new_file("/dev/usb/lp0");
+    /// ```
+    ///
+    /// This is the simplest transport, the bytes are written to the path as they are. It works with device files (when permissions allow it, where rusb might fail) and with raw CUPS queues. By default, the file is opened when the printer is created, and kept open. See [with_keep_open](PrinterProfileBuilder::with_keep_open) to open it for each write instead. The same defaults as [new_usb](PrinterProfileBuilder::new_usb) apply for fonts and width.
+    pub fn new_file<A: Into<std::path::PathBuf>>(path: A) -> PrinterProfileBuilder {
+        PrinterProfileBuilder {
+            printer_connection_data: PrinterConnectionData::File {
+                path: path.into(),
+                keep_open: true
+            },
+            columns_per_font: vec![(Font::FontA, 32)].into_iter().collect(),
+            width: 384,
+            default_code_table: CodeTable::USA,
+            supports_native_qr: false,
+            max_transfer_size: 4096,
+            has_cutter: true,
+            cut_command: Command::Cut.as_bytes(),
+            supported_image_modes: default_image_modes(),
+            font_select_bytes: HashMap::new(),
+            auto_init: false,
+            has_black_mark_sensor: false,
+            supports_real_time: false,
+            has_presenter: false,
+            supports_color: false,
+            sync_before_cut: false,
+            terminal_writer: None
         }
     }
 
@@ -129,10 +489,57 @@ impl PrinterProfileBuilder {
         PrinterProfileBuilder {
             printer_connection_data: PrinterConnectionData::Terminal,
             columns_per_font: vec![(Font::FontA, 32)].into_iter().collect(),
-            width: 384
+            width: 384,
+            default_code_table: CodeTable::USA,
+            supports_native_qr: false,
+            max_transfer_size: 4096,
+            has_cutter: true,
+            cut_command: Command::Cut.as_bytes(),
+            supported_image_modes: default_image_modes(),
+            font_select_bytes: HashMap::new(),
+            auto_init: false,
+            has_black_mark_sensor: false,
+            supports_real_time: false,
+            has_presenter: false,
+            supports_color: false,
+            sync_before_cut: false,
+            terminal_writer: None
         }
     }
 
+    /// Creates a new [PrinterProfileBuilder](crate::PrinterProfileBuilder) set for terminal printing, to the given writer
+    ///
+    /// Same as [new_terminal](PrinterProfileBuilder::new_terminal), but the text goes to the writer instead of the standard output, which makes the preview easy to capture in tests or to embed in an application. Errors while writing are ignored. The writer is not part of the serialized profile.
+    ///
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfileBuilder};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // Writer that keeps the output, shared with the test
+    /// #[derive(Clone, Default)]
+    /// struct Capture(Arc<Mutex<Vec<u8>>>);
+    /// impl std::io::Write for Capture {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.0.lock().unwrap().write(buf)
+    ///     }
+    ///     fn flush(&mut self) -> std::io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let capture = Capture::default();
+    /// let printer_profile = PrinterProfileBuilder::new_terminal_with(Box::new(capture.clone())).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.println("Total: 5.00")?;
+    /// assert_eq!(&b"Total: 5.00\n"[..], capture.0.lock().unwrap().as_slice());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn new_terminal_with(writer: Box<dyn std::io::Write + Send>) -> PrinterProfileBuilder {
+        let mut builder = PrinterProfileBuilder::new_terminal();
+        builder.terminal_writer = Some(TerminalWriter(Arc::new(Mutex::new(writer))));
+        builder
+    }
+
     /// Sets the usb endpoint to which the data will be written.
     ///
     /// ```rust
@@ -180,6 +587,89 @@ impl PrinterProfileBuilder {
         self
     }
 
+    /// Sets the bytes sent to select a font
+    ///
+    /// By default, fonts get selected with `ESC M n`, but some printers map the fonts to other values, or need the whole print mode (`ESC !`). The bytes get used wherever the library selects the font, like text instructions and styles.
+    /// ```rust
+    /// use escpos_rs::{Instruction, Justification, PrinterProfile, command::{Command, Font}};
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_font_width(Font::FontB, 42)
+    ///     .with_font_command(Font::FontB, vec![0x1b, 0x21, 0x01])
+    ///     .build();
+    /// let bytes = Instruction::text("Small print", Font::FontB, Justification::Left, None).to_vec(&printer_profile, None)?;
+    /// assert!(bytes.starts_with(&[0x1b, 0x21, 0x01]));
+    /// assert!(!bytes.windows(3).any(|window| window == Command::SelectFont{font: Font::FontB}.as_bytes().as_slice()));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_font_command(mut self, font: Font, bytes: Vec<u8>) -> PrinterProfileBuilder {
+        self.font_select_bytes.insert(font, bytes);
+        self
+    }
+
+    /// Sets the code table the printer uses when powered on
+    ///
+    /// Defaults to [CodeTable::USA](crate::command::CodeTable::USA). If a different code table is given, it gets selected when connecting to the printer.
+    /// ```rust
+    /// use escpos_rs::{PrinterProfileBuilder, command::CodeTable};
+    /// let printer_profile = PrinterProfileBuilder::new_usb(0x0001, 0x0001)
+    ///     .with_default_code_table(CodeTable::PC850)
+    ///     .build();
+    /// ```
+    pub fn with_default_code_table(mut self, code_table: CodeTable) -> PrinterProfileBuilder {
+        self.default_code_table = code_table;
+        self
+    }
+
+    /// Indicates if the printer can generate QR codes by itself
+    ///
+    /// Dynamic QR codes get printed with the `GS ( k` command on printers that support it, and as images otherwise. Defaults to `false`, as the image always works.
+    /// ```rust
+    /// use escpos_rs::PrinterProfileBuilder;
+    /// let printer_profile = PrinterProfileBuilder::new_usb(0x0001, 0x0001)
+    ///     .with_native_qr(true)
+    ///     .build();
+    /// ```
+    pub fn with_native_qr(mut self, supports_native_qr: bool) -> PrinterProfileBuilder {
+        self.supports_native_qr = supports_native_qr;
+        self
+    }
+
+    /// Sets the maximum number of bytes sent to the printer in a single transfer
+    ///
+    /// Some hosts can't handle big transfers (images, for example), so the data gets split into pieces of at most this size. Pieces the device accepts only in part get sent again from where they stopped. Over usb, each piece adds the profile's timeout to the time the whole write can take. Defaults to 4096 bytes.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfileBuilder, Transport, Error};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // Accepts at most 3 bytes per write, and keeps each one
+    /// struct Narrow(Arc<Mutex<Vec<Vec<u8>>>>);
+    ///
+    /// impl Transport for Narrow {
+    ///     fn write(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+    ///         let count = bytes.len().min(3);
+    ///         self.0.lock().unwrap().push(bytes[..count].to_vec());
+    ///         Ok(count)
+    ///     }
+    /// }
+    ///
+    /// let printer_profile = PrinterProfileBuilder::new_usb(0x0001, 0x0001)
+    ///     .with_max_transfer_size(4)
+    ///     .build();
+    /// let writes = Arc::new(Mutex::new(Vec::new()));
+    /// let printer = Printer::new_with_transport(printer_profile, Box::new(Narrow(writes.clone())))?;
+    /// printer.raw(b"0123456789")?;
+    ///
+    /// // Pieces of 4, 4 and 2 bytes, the first two split by the transport
+    /// let writes = writes.lock().unwrap();
+    /// assert_eq!(vec![3, 1, 3, 1, 2], writes.iter().map(|write| write.len()).collect::<Vec<_>>());
+    /// assert_eq!(b"0123456789".to_vec(), writes.concat());
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_max_transfer_size(mut self, max_transfer_size: usize) -> PrinterProfileBuilder {
+        self.max_transfer_size = max_transfer_size;
+        self
+    }
+
     /// Adds a bulk write timeout (usb only)
     ///
     /// USB devices might fail to write to the bulk endpoint. In such a case, a timeout must be provided to know when to stop waiting for the buffer to flush to the printer. The default value is 2 seconds.
@@ -199,6 +689,171 @@ impl PrinterProfileBuilder {
         }
     }
 
+    /// Keeps the `usblp` kernel driver attached to the printer (Linux only)
+    ///
+    /// By default, the kernel driver gets detached to claim the usb interface, which breaks other software relying on it. With this option, if the driver is bound to the printer, the data gets written to its character device (like `/dev/usb/lp0`) instead. When no character device is found, the usual usb connection is used. See [find_usblp_device](crate::Printer::find_usblp_device).
+    /// ```rust
+    /// use escpos_rs::PrinterProfileBuilder;
+    /// let printer_profile = PrinterProfileBuilder::new_usb(0x0001, 0x0001)
+    ///     .with_kernel_driver(true).unwrap()
+    ///     .build();
+    /// ```
+    pub fn with_kernel_driver(mut self, keep_kernel_driver: bool) -> Result<PrinterProfileBuilder, Error> {
+        match &mut self.printer_connection_data {
+            PrinterConnectionData::Usb{keep_kernel_driver: self_keep_kernel_driver, ..} => {
+                *self_keep_kernel_driver = keep_kernel_driver;
+                Ok(self)
+            },
+            _other => Err(Error::UnsupportedForPrinterConnection)
+        }
+    }
+
+    /// Sets if the printer has an autocutter
+    ///
+    /// Printers without a cutter might print garbage when receiving the cut command. When set to `false`, cuts get replaced by a few line feeds, to reach the tear bar. By default, the printer is assumed to have a cutter.
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile, command::Command};
+    /// let printer_profile = PrinterProfile::terminal_builder().with_cutter(false).build();
+    /// let bytes = Instruction::cut().to_vec(&printer_profile, None)?;
+    /// assert_eq!(Command::FeedLines{lines: 4}.as_bytes(), bytes);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_cutter(mut self, has_cutter: bool) -> PrinterProfileBuilder {
+        self.has_cutter = has_cutter;
+        self
+    }
+
+    /// Sets the bytes sent to cut the paper
+    ///
+    /// Not every printer understands the same cut command. By default, [Command::Cut](crate::command::Command::Cut) is used (`GS V A 0x96`, feed and cut), while some models expect `GS V B 0` or just `GS V 1`. The [printer models](crate::PrinterModel) come with the right one.
+    /// ```rust
+    /// use escpos_rs::{Instruction, PrinterProfile};
+    /// let printer_profile = PrinterProfile::terminal_builder().with_cut_command(vec![0x1d, 0x56, 0x01]).build();
+    /// assert_eq!(vec![0x1d, 0x56, 0x01], Instruction::cut().to_vec(&printer_profile, None)?);
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_cut_command(mut self, cut_command: Vec<u8>) -> PrinterProfileBuilder {
+        self.cut_command = cut_command;
+        self
+    }
+
+    /// Sets the bit image modes the printer understands
+    ///
+    /// Images get printed with their own [mode](crate::EscposImage::with_image_mode), but if the printer does not understand it, the [closest](crate::ImageMode::closest_in) supported one gets used instead, as 24 dot images print garbage on printers that only know the 8 dot modes. With no modes at all, printing an image gives back a [NoImageModeFound](crate::Error::NoImageModeFound) error. By default, all modes are supported.
+    /// ```rust
+    /// use escpos_rs::{Error, EscposImage, Instruction, ImageMode, Justification, PrinterProfile};
+    /// let printer_profile = PrinterProfile::terminal_builder()
+    ///     .with_image_modes(vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble])
+    ///     .build();
+    /// let logo = EscposImage::new(image::DynamicImage::new_rgba8(8, 8), 255, Justification::Left)?
+    ///     .with_image_mode(ImageMode::TwentyFourDotDouble);
+    /// // The image falls back to the 8 dot mode
+    /// let bytes = Instruction::image(logo.clone())?.to_vec(&printer_profile, None)?;
+    /// assert!(bytes.windows(3).any(|window| window == [0x1b, 0x2a, 0x01]));
+    /// assert!(!bytes.windows(3).any(|window| window == [0x1b, 0x2a, 0x21]));
+    ///
+    /// let printer_profile = PrinterProfile::terminal_builder().with_image_modes(vec![]).build();
+    /// assert!(matches!(Instruction::image(logo)?.to_vec(&printer_profile, None), Err(Error::NoImageModeFound)));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_image_modes(mut self, supported_image_modes: Vec<ImageMode>) -> PrinterProfileBuilder {
+        self.supported_image_modes = supported_image_modes;
+        self
+    }
+
+    /// Sets if each print starts by initializing the printer (`ESC @`)
+    ///
+    /// Some printers keep state from previous jobs (styles, code tables, half-sent images), the initialization guarantees a clean start for each receipt. It applies to each call of the [Printer](crate::Printer)'s [instruction](crate::Printer::instruction), [instruction_streamed](crate::Printer::instruction_streamed) (once per instruction), [instruction_copies](crate::Printer::instruction_copies) (once per copy), [print_stub](crate::Printer::print_stub) and [print](crate::Printer::print) (so, [println](crate::Printer::println) too). The cost is two extra bytes and a short pause while the printer resets, but also that the settings sent before (like a font selected with [set_font](crate::Printer::set_font)) get lost. The code table the text gets encoded with is the exception, it gets selected again right after the initialization. By default, no initialization is sent.
+    /// ```rust
+    /// use escpos_rs::{Printer, PrinterProfile, command::{Command, CodeTable}};
+    /// let path = std::env::temp_dir().join("escpos-rs-auto-init-example.bin");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let printer = Printer::new(PrinterProfile::file_builder(&path).with_auto_init(true).build())?.unwrap();
+    /// printer.println("Receipt")?;
+    ///
+    /// let mut target = Command::Init.as_bytes();
+    /// target.extend_from_slice(b"Receipt\n");
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    ///
+    /// // With another code table, it follows the initialization
+    /// let printer_profile = PrinterProfile::file_builder(&path).with_auto_init(true).with_default_code_table(CodeTable::PC850).build();
+    /// let printer = Printer::new(printer_profile)?.unwrap();
+    /// printer.println("Receipt")?;
+    ///
+    /// // The code table also gets selected when the printer is created
+    /// let mut target = Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes();
+    /// target.append(&mut Command::Init.as_bytes());
+    /// target.append(&mut Command::SelectCodeTable{code_table: CodeTable::PC850}.as_bytes());
+    /// target.extend_from_slice(b"Receipt\n");
+    /// assert_eq!(target, std::fs::read(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn with_auto_init(mut self, auto_init: bool) -> PrinterProfileBuilder {
+        self.auto_init = auto_init;
+        self
+    }
+
+    /// Sets if the printer has a black mark sensor
+    ///
+    /// Required to use the [Printer](crate::Printer)'s [feed_to_mark](crate::Printer::feed_to_mark) method, with paper that has black marks printed on its back (pre-printed labels or tickets). By default, no sensor is assumed.
+    pub fn with_black_mark_sensor(mut self, has_black_mark_sensor: bool) -> PrinterProfileBuilder {
+        self.has_black_mark_sensor = has_black_mark_sensor;
+        self
+    }
+
+    /// Sets if the printer processes real-time commands
+    ///
+    /// Real-time commands (starting with `DLE`) get executed as soon as they arrive, even when the printer is in an error state. Printers without support print them as garbage, so they are disabled by default. Required for the [Printer](crate::Printer)'s [recover](crate::Printer::recover) and [clear_buffer](crate::Printer::clear_buffer) methods.
+    pub fn with_real_time_commands(mut self, supports_real_time: bool) -> PrinterProfileBuilder {
+        self.supports_real_time = supports_real_time;
+        self
+    }
+
+    /// Sets if the printer has a paper presenter
+    ///
+    /// Kiosk and label printers with a presenter (or peeler) hold the printed paper until it gets presented to the customer, or retracted. Required for the [Printer](crate::Printer)'s [present_label](crate::Printer::present_label) and [retract_label](crate::Printer::retract_label) methods. By default, no presenter is assumed.
+    pub fn with_presenter(mut self, has_presenter: bool) -> PrinterProfileBuilder {
+        self.has_presenter = has_presenter;
+        self
+    }
+
+    /// Sets if the printer prints in two colors
+    ///
+    /// Two-color printers, with the proper ribbon or paper, can print text in a second color (usually red). Required for the [Printer](crate::Printer)'s [set_text_color](crate::Printer::set_text_color) method. By default, single color printing is assumed.
+    pub fn with_color(mut self, supports_color: bool) -> PrinterProfileBuilder {
+        self.supports_color = supports_color;
+        self
+    }
+
+    /// Sets if cuts wait for the printer to consume the previous data
+    ///
+    /// Over usb, writes return once the data is queued, not once it is printed, so a cut right after a big print can land in the middle of the receipt. With this option, the [Printer](crate::Printer)'s [cut](crate::Printer::cut) first asks for the printer status (`DLE EOT`) and waits for the answer, see [sync](crate::Printer::sync). It requires [real-time commands](PrinterProfileBuilder::with_real_time_commands) and an usb connection with a bulk in endpoint, otherwise the cut gets sent right away. Disabled by default.
+    pub fn with_sync_before_cut(mut self, sync_before_cut: bool) -> PrinterProfileBuilder {
+        self.sync_before_cut = sync_before_cut;
+        self
+    }
+
+    /// Sets if the file of a file connection is kept open between writes
+    ///
+    /// When `false`, the file gets opened in append mode for each write, and closed right after. Useful when another process consumes the file between prints.
+    /// ```rust
+    /// use escpos_rs::PrinterProfileBuilder;
+    /// let printer_profile = PrinterProfileBuilder::new_file("/var/spool/receipts.bin")
+    ///     .with_keep_open(false).unwrap()
+    ///     .build();
+    /// ```
+    pub fn with_keep_open(mut self, keep_open: bool) -> Result<PrinterProfileBuilder, Error> {
+        match &mut self.printer_connection_data {
+            PrinterConnectionData::File{keep_open: self_keep_open, ..} => {
+                *self_keep_open = keep_open;
+                Ok(self)
+            },
+            _other => Err(Error::UnsupportedForPrinterConnection)
+        }
+    }
+
     /// Build the `PrinterProfile` that lies beneath the builder
     ///
     /// ```rust
@@ -209,7 +864,58 @@ impl PrinterProfileBuilder {
         PrinterProfile {
             printer_connection_data: self.printer_connection_data,
             columns_per_font: self.columns_per_font,
-            width: self.width
+            width: self.width,
+            default_code_table: self.default_code_table,
+            supports_native_qr: self.supports_native_qr,
+            max_transfer_size: self.max_transfer_size,
+            has_cutter: self.has_cutter,
+            cut_command: self.cut_command,
+            supported_image_modes: self.supported_image_modes,
+            font_select_bytes: self.font_select_bytes,
+            auto_init: self.auto_init,
+            has_black_mark_sensor: self.has_black_mark_sensor,
+            supports_real_time: self.supports_real_time,
+            has_presenter: self.has_presenter,
+            supports_color: self.supports_color,
+            sync_before_cut: self.sync_before_cut,
+            terminal_writer: self.terminal_writer
         }
     }
-}
\ No newline at end of file
+}
+
+/// Serialization of durations as milliseconds
+mod duration_millis {
+    use serde::{Deserialize, Serializer, Deserializer};
+
+    pub fn serialize<S: Serializer>(duration: &std::time::Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<std::time::Duration, D::Error> {
+        u64::deserialize(deserializer).map(std::time::Duration::from_millis)
+    }
+}
+
+fn default_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(2)
+}
+
+fn default_code_table() -> CodeTable {
+    CodeTable::USA
+}
+
+fn default_cut_command() -> Vec<u8> {
+    Command::Cut.as_bytes()
+}
+
+fn default_image_modes() -> Vec<ImageMode> {
+    vec![ImageMode::EightDotSingle, ImageMode::EightDotDouble, ImageMode::TwentyFourDotSingle, ImageMode::TwentyFourDotDouble]
+}
+
+fn default_max_transfer_size() -> usize {
+    4096
+}
+
+fn default_true() -> bool {
+    true
+}
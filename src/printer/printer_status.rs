@@ -0,0 +1,113 @@
+use crate::Error;
+use std::time::{Duration, Instant};
+
+/// Time between status requests, while waiting for the printer
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// State of the printer, as reported by the `DLE EOT` real-time requests
+///
+/// Obtained through the [Printer](crate::Printer)'s [status](crate::Printer::status) method. Its display is a one line summary, meant for logs and dashboards.
+///
+/// ```rust
+/// use escpos_rs::PrinterStatus;
+/// // Online, cover closed, no errors, paper near its end
+/// let status = PrinterStatus::from_bytes(0x12, 0x12, 0x12, 0x1e);
+/// assert_eq!("online, paper near end, cover closed", status.to_string());
+///
+/// // Offline because of the open cover, without paper
+/// let status = PrinterStatus::from_bytes(0x1a, 0x36, 0x12, 0x72);
+/// assert_eq!("offline, paper out, cover open", status.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PrinterStatus {
+    /// If the printer is ready to print
+    pub online: bool,
+    /// If the cover is open
+    pub cover_open: bool,
+    /// If the paper roll is about to end
+    pub paper_near_end: bool,
+    /// If there is no paper left
+    pub paper_out: bool,
+    /// If the printer stopped because of an error (cutter jam, overheating, and such)
+    pub error: bool
+}
+
+impl PrinterStatus {
+    /// Decodes the answers to the four status requests
+    ///
+    /// Each byte is the answer to [TransmitStatus](crate::command::Command::TransmitStatus) with the status 1 (printer), 2 (offline cause), 3 (error cause) and 4 (paper roll sensor), respectively.
+    pub fn from_bytes(printer: u8, offline: u8, error: u8, paper: u8) -> PrinterStatus {
+        PrinterStatus {
+            online: printer & 0x08 == 0,
+            cover_open: offline & 0x04 != 0,
+            paper_near_end: paper & 0x0c != 0,
+            paper_out: offline & 0x20 != 0 || paper & 0x60 != 0,
+            // Autocutter, unrecoverable and auto-recoverable errors
+            error: offline & 0x40 != 0 || error & 0x68 != 0
+        }
+    }
+
+    /// Indicates if the printer can print right away: online, with the cover closed and paper in it
+    pub fn is_ready(&self) -> bool {
+        self.online && !self.cover_open && !self.paper_out
+    }
+
+    /// Polls a status source until it reports a [ready](PrinterStatus::is_ready) printer
+    ///
+    /// The source gets called right away, and then every 100 milliseconds, until the status is ready or the timeout elapses, in which case a [Timeout](crate::Error::Timeout) error is returned. Errors from the source stop the polling. This is the loop behind the [Printer](crate::Printer)'s [wait_online](crate::Printer::wait_online), available for other status sources.
+    ///
+    /// ```rust
+    /// use escpos_rs::{PrinterStatus, Error};
+    /// use std::time::Duration;
+    ///
+    /// // The cover gets closed after a couple of polls
+    /// let mut polls = 0;
+    /// let status = PrinterStatus::wait_online(|| {
+    ///     polls += 1;
+    ///     Ok(PrinterStatus {
+    ///         online: polls > 2,
+    ///         cover_open: polls <= 2,
+    ///         ..PrinterStatus::default()
+    ///     })
+    /// }, Duration::from_secs(5))?;
+    /// assert!(status.is_ready());
+    /// assert_eq!(3, polls);
+    ///
+    /// // A printer that never gets ready
+    /// let offline = PrinterStatus::wait_online(|| Ok(PrinterStatus::default()), Duration::from_millis(250));
+    /// assert!(matches!(offline, Err(Error::Timeout)));
+    /// # Ok::<(), escpos_rs::Error>(())
+    /// ```
+    pub fn wait_online<F: FnMut() -> Result<PrinterStatus, Error>>(mut source: F, timeout: Duration) -> Result<PrinterStatus, Error> {
+        let start = Instant::now();
+        loop {
+            let status = source()?;
+            if status.is_ready() {
+                return Ok(status);
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(Error::Timeout);
+            }
+            std::thread::sleep(POLL_INTERVAL.min(timeout - elapsed));
+        }
+    }
+}
+
+impl std::fmt::Display for PrinterStatus {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let paper = if self.paper_out {
+            "paper out"
+        } else if self.paper_near_end {
+            "paper near end"
+        } else {
+            "paper OK"
+        };
+        write!(formatter, "{}, {}, {}{}",
+            if self.online { "online" } else { "offline" },
+            paper,
+            if self.cover_open { "cover open" } else { "cover closed" },
+            if self.error { ", error" } else { "" }
+        )
+    }
+}
@@ -0,0 +1,44 @@
+use super::Printer;
+use crate::{Diagnostic, command::Command, diagnostic::report};
+
+/// Keeps a style active in the printer, until it gets dropped
+///
+/// Created through the printer's [bold_scope](Printer::bold_scope), [underline_scope](Printer::underline_scope) and [inverse_scope](Printer::inverse_scope) methods. The scope gives access to the printer, so everything printed through it gets the style. When the scope is dropped, the style gets turned off.
+///
+/// As a drop cannot return errors, a failure to turn the style off gets reported as a [ScopeEndFailed](crate::Diagnostic::ScopeEndFailed) diagnostic.
+pub struct StyleScope<'a> {
+    printer: &'a mut Printer,
+    /// Command that turns the style off
+    off: Command
+}
+
+impl<'a> StyleScope<'a> {
+    pub(crate) fn new(printer: &'a mut Printer, off: Command) -> StyleScope<'a> {
+        StyleScope {
+            printer,
+            off
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for StyleScope<'a> {
+    type Target = Printer;
+
+    fn deref(&self) -> &Printer {
+        self.printer
+    }
+}
+
+impl<'a> std::ops::DerefMut for StyleScope<'a> {
+    fn deref_mut(&mut self) -> &mut Printer {
+        self.printer
+    }
+}
+
+impl<'a> Drop for StyleScope<'a> {
+    fn drop(&mut self) {
+        if let Err(e) = self.printer.style(&self.off) {
+            report(self.printer.handler(), Diagnostic::ScopeEndFailed{reason: e.to_string()});
+        }
+    }
+}
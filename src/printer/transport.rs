@@ -0,0 +1,44 @@
+use crate::Error;
+
+/// Link to a printer that this library does not handle itself
+///
+/// Useful to print through a serial port, a bluetooth socket or any other channel, and to test the bytes a [Printer](crate::Printer) sends without a device. The printer gets created with [new_with_transport](crate::Printer::new_with_transport), and then works as with an usb connection: writes get split according to the profile's [maximum transfer size](crate::PrinterProfileBuilder::with_max_transfer_size), and status requests use [read](Transport::read) if the transport [can read](Transport::can_read).
+///
+/// ```rust
+/// use escpos_rs::{Printer, PrinterProfile, Transport, Error};
+/// use std::sync::{Arc, Mutex};
+///
+/// // Keeps everything that gets written
+/// struct Recorder(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Transport for Recorder {
+///     fn write(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+///         self.0.lock().unwrap().extend_from_slice(bytes);
+///         Ok(bytes.len())
+///     }
+/// }
+///
+/// let written = Arc::new(Mutex::new(Vec::new()));
+/// let printer = Printer::new_with_transport(PrinterProfile::terminal_builder().build(), Box::new(Recorder(written.clone())))?;
+/// printer.println("Hello")?;
+/// assert_eq!(&b"Hello\n"[..], written.lock().unwrap().as_slice());
+/// # Ok::<(), escpos_rs::Error>(())
+/// ```
+pub trait Transport: Send {
+    /// Sends part of the bytes to the printer, giving back how many of them were accepted
+    ///
+    /// Accepting none of them is treated as an error.
+    fn write(&mut self, bytes: &[u8]) -> Result<usize, Error>;
+
+    /// Reads the bytes sent back by the printer, giving back how many arrived (0 if none did in time)
+    ///
+    /// By default, reading is not supported.
+    fn read(&mut self, _buffer: &mut [u8]) -> Result<usize, Error> {
+        Err(Error::UnsupportedForPrinterConnection)
+    }
+
+    /// If the transport can read the answers of the printer, like the status
+    fn can_read(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use crate::{
+    Justification,
+    PrinterProfile,
+    command::{Command, Font}
+};
+
+/// Set of text attributes, to be applied together
+///
+/// Attributes left as `false` or `None` are not touched when the style gets applied.
+#[derive(Clone, Debug, Default)]
+pub struct TextStyle {
+    /// Bold text
+    pub bold: bool,
+    /// Underlined text, with a 1 dot line
+    pub underline: bool,
+    /// Font to use for the text
+    pub font: Option<Font>,
+    /// Width and height multipliers of the characters, from 1 to 8
+    pub size: Option<(u8, u8)>,
+    /// Justification of the text
+    pub justification: Option<Justification>
+}
+
+impl TextStyle {
+    /// Commands that turn the style on
+    pub(crate) fn on_bytes(&self, printer_profile: &PrinterProfile) -> Vec<u8> {
+        let mut res = Vec::new();
+        if let Some(font) = &self.font {
+            res.append(&mut printer_profile.font_bytes(font));
+        }
+        if let Some((width, height)) = self.size {
+            res.append(&mut Command::SelectCharacterSize{width, height}.as_bytes());
+        }
+        if self.bold {
+            res.append(&mut Command::BoldOn.as_bytes());
+        }
+        if self.underline {
+            res.append(&mut Command::Underline1Dot.as_bytes());
+        }
+        if let Some(justification) = &self.justification {
+            res.append(&mut Command::SelectJustification{justification: justification.clone()}.as_bytes());
+        }
+        res
+    }
+
+    /// Commands that restore the defaults for the attributes of the style, and the given font and size
+    pub(crate) fn off_bytes(&self, font: &Font, text_size: (u8, u8), printer_profile: &PrinterProfile) -> Vec<u8> {
+        let mut res = Vec::new();
+        if self.justification.is_some() {
+            res.append(&mut Command::SelectJustification{justification: Justification::Left}.as_bytes());
+        }
+        if self.underline {
+            res.append(&mut Command::UnderlineOff.as_bytes());
+        }
+        if self.bold {
+            res.append(&mut Command::BoldOff.as_bytes());
+        }
+        if self.size.is_some() {
+            res.append(&mut Command::SelectCharacterSize{width: text_size.0, height: text_size.1}.as_bytes());
+        }
+        if self.font.is_some() {
+            res.append(&mut printer_profile.font_bytes(font));
+        }
+        res
+    }
+}
+
+/// Named text styles, for recurrent prints
+///
+/// Keeps a handful of presets (like a title, or the total of a receipt) that can be used by name with the [Printer](crate::Printer)'s [println_style](crate::Printer::println_style) method.
+///
+/// ```rust
+/// use escpos_rs::{StyleSheet, TextStyle, Justification};
+///
+/// let style_sheet = StyleSheet::new()
+///     .with_style("title", TextStyle {
+///         bold: true,
+///         justification: Some(Justification::Center),
+///         ..Default::default()
+///     })
+///     .with_style("total", TextStyle {
+///         size: Some((2, 2)),
+///         ..Default::default()
+///     });
+/// assert!(style_sheet.get("title").is_some());
+/// assert!(style_sheet.get("small").is_none());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StyleSheet {
+    styles: HashMap<String, TextStyle>
+}
+
+impl StyleSheet {
+    /// Creates an empty style sheet
+    pub fn new() -> StyleSheet {
+        StyleSheet::default()
+    }
+
+    /// Adds a style to the sheet, replacing any style with the same name
+    pub fn with_style<A: Into<String>>(mut self, name: A, style: TextStyle) -> StyleSheet {
+        self.styles.insert(name.into(), style);
+        self
+    }
+
+    /// Gives back the style with the given name, if any
+    pub fn get(&self, name: &str) -> Option<&TextStyle> {
+        self.styles.get(name)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Instruction;
+use serde::{Serialize, Deserialize};
+
+/// Common header and footer shared by several kinds of prints
+///
+/// Receipts of a business usually share the same header (logo, address) and footer (thanks, a qr code), with only the body changing. The template holds both ends, and [render](Template::render) puts a body between them.
+///
+/// ```rust
+/// use escpos_rs::{Instruction, Justification, PrinterProfile, Template, command::Font};
+///
+/// let printer_profile = PrinterProfile::terminal_builder().build();
+/// let header = Instruction::text("Coffee shop", Font::FontA, Justification::Center, None);
+/// let body = Instruction::text("Espresso 2.00", Font::FontA, Justification::Left, None);
+/// let footer = Instruction::text("Thanks!", Font::FontA, Justification::Center, None);
+///
+/// let template = Template::new()
+///     .with_header(header.clone())
+///     .with_footer(footer.clone());
+/// let instruction = template.render(body.clone());
+///
+/// let mut target = header.to_vec(&printer_profile, None)?;
+/// target.append(&mut body.to_vec(&printer_profile, None)?);
+/// target.append(&mut footer.to_vec(&printer_profile, None)?);
+/// assert_eq!(target, instruction.to_vec(&printer_profile, None)?);
+/// # Ok::<(), escpos_rs::Error>(())
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Template {
+    /// Printed before the body
+    pub header: Option<Instruction>,
+    /// Printed after the body
+    pub footer: Option<Instruction>
+}
+
+impl Template {
+    /// Creates a template without header nor footer
+    pub fn new() -> Template {
+        Template::default()
+    }
+
+    /// Sets the header of the template
+    pub fn with_header(mut self, header: Instruction) -> Template {
+        self.header = Some(header);
+        self
+    }
+
+    /// Sets the footer of the template
+    pub fn with_footer(mut self, footer: Instruction) -> Template {
+        self.footer = Some(footer);
+        self
+    }
+
+    /// Places the body between the header and the footer
+    pub fn render(&self, body: Instruction) -> Instruction {
+        let mut instruction = match &self.header {
+            Some(header) => header.clone() + body,
+            None => body
+        };
+        if let Some(footer) = &self.footer {
+            instruction += footer.clone();
+        }
+        instruction
+    }
+}
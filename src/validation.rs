@@ -0,0 +1,213 @@
+/// Suspicious spot found while validating a buffer, see [validate_escpos](crate::validate_escpos)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    /// Position of the first byte of the command in the buffer
+    pub offset: usize,
+    /// Name of the command, as in the esc/pos manuals
+    pub command: &'static str,
+    /// Bytes the command should take, including its prefix
+    pub expected: usize,
+    /// Bytes left in the buffer from the offset
+    pub found: usize
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "{} at offset {} is truncated, expected {} bytes but found {}", self.command, self.offset, self.expected, self.found)
+    }
+}
+
+/// Little-endian 16 bit number from two bytes of the buffer, if present
+fn read_u16(bytes: &[u8], idx: usize) -> Option<usize> {
+    Some(*bytes.get(idx)? as usize + 256 * *bytes.get(idx + 1)? as usize)
+}
+
+/// Length of the command starting at the given offset, as (name, length)
+///
+/// The length might not be computable if the buffer ends within the header, in that case the minimum length for the header is given. Unknown commands take just their prefix and command byte.
+fn command_length(bytes: &[u8], idx: usize) -> (&'static str, usize) {
+    let prefix = bytes[idx];
+    let command = match bytes.get(idx + 1) {
+        Some(command) => *command,
+        None => return (if prefix == 0x1b { "ESC" } else { "GS" }, 2)
+    };
+    match (prefix, command) {
+        (0x1b, b'@') => ("ESC @", 2),
+        (0x1b, b'2') => ("ESC 2", 2),
+        (0x1b, b'!') => ("ESC !", 3),
+        (0x1b, b'-') => ("ESC -", 3),
+        (0x1b, b'%') => ("ESC %", 3),
+        (0x1b, b'3') => ("ESC 3", 3),
+        (0x1b, b'E') => ("ESC E", 3),
+        (0x1b, b'J') => ("ESC J", 3),
+        (0x1b, b'M') => ("ESC M", 3),
+        (0x1b, b'R') => ("ESC R", 3),
+        (0x1b, b'a') => ("ESC a", 3),
+        (0x1b, b'd') => ("ESC d", 3),
+        (0x1b, b'e') => ("ESC e", 3),
+        (0x1b, b't') => ("ESC t", 3),
+        (0x1b, b'{') => ("ESC {", 3),
+        (0x1b, b'r') => ("ESC r", 3),
+        (0x1b, b'p') => ("ESC p", 5),
+        (0x1b, b'D') => {
+            // Tab positions, up to a NUL byte
+            match bytes[idx + 2..].iter().position(|byte| *byte == 0x00) {
+                Some(position) => ("ESC D", position + 3),
+                None => ("ESC D", bytes.len() - idx + 1)
+            }
+        },
+        (0x1b, b'*') => {
+            let columns = match read_u16(bytes, idx + 3) {
+                Some(columns) => columns,
+                None => return ("ESC *", 5)
+            };
+            // Modes 0 and 1 take one byte per column, the 24 dot modes take three
+            let bytes_per_column = if bytes[idx + 2] < 2 { 1 } else { 3 };
+            ("ESC *", 5 + columns * bytes_per_column)
+        },
+        (0x1b, b'&') => {
+            let (height, first, last) = match (bytes.get(idx + 2), bytes.get(idx + 3), bytes.get(idx + 4)) {
+                (Some(height), Some(first), Some(last)) => (*height as usize, *first, *last),
+                _ => return ("ESC &", 5)
+            };
+            // Each character carries its width, followed by its columns
+            let mut length = 5;
+            for _ in first..=last {
+                match bytes.get(idx + length) {
+                    Some(width) => length += 1 + height * (*width as usize),
+                    None => return ("ESC &", length + 1)
+                }
+            }
+            ("ESC &", length)
+        },
+        (0x1d, b'@') => ("GS @", 2),
+        (0x1d, b'!') => ("GS !", 3),
+        (0x1d, b'B') => ("GS B", 3),
+        (0x1d, b'H') => ("GS H", 3),
+        (0x1d, b'f') => ("GS f", 3),
+        (0x1d, b'h') => ("GS h", 3),
+        (0x1d, b'w') => ("GS w", 3),
+        (0x1d, b'/') => ("GS /", 3),
+        (0x1d, b'e') => {
+            match bytes.get(idx + 2) {
+                // Presenting the paper carries its length
+                Some(0x03) => ("GS e", 4),
+                _ => ("GS e", 3)
+            }
+        },
+        (0x1d, b'L') => ("GS L", 4),
+        (0x1d, b'W') => ("GS W", 4),
+        (0x1d, b'V') => {
+            match bytes.get(idx + 2) {
+                // Cuts with feed carry an extra argument
+                Some(0x41) | Some(0x42) | Some(0x61) | Some(0x62) => ("GS V", 4),
+                _ => ("GS V", 3)
+            }
+        },
+        (0x1d, b'k') => {
+            match (bytes.get(idx + 2), bytes.get(idx + 3)) {
+                // The newer barcode types carry the length of their data
+                (Some(kind), Some(length)) if *kind >= 65 => ("GS k", 4 + *length as usize),
+                (Some(kind), _) if *kind >= 65 => ("GS k", 4),
+                // The older ones end their data with a NUL byte
+                (Some(_), _) => match bytes[idx + 3..].iter().position(|byte| *byte == 0x00) {
+                    Some(position) => ("GS k", position + 4),
+                    None => ("GS k", bytes.len() - idx + 1)
+                },
+                _ => ("GS k", 4)
+            }
+        },
+        (0x1d, b'*') => {
+            match (bytes.get(idx + 2), bytes.get(idx + 3)) {
+                // Eight dots high columns, in blocks of x by y
+                (Some(x), Some(y)) => ("GS *", 4 + 8 * (*x as usize) * (*y as usize)),
+                _ => ("GS *", 4)
+            }
+        },
+        (0x1d, b'(') => {
+            match read_u16(bytes, idx + 3) {
+                Some(length) => ("GS (", 5 + length),
+                None => ("GS (", 5)
+            }
+        },
+        (0x1d, b'v') => {
+            match (read_u16(bytes, idx + 4), read_u16(bytes, idx + 6)) {
+                (Some(width), Some(height)) => ("GS v 0", 8 + width * height),
+                _ => ("GS v 0", 8)
+            }
+        },
+        (0x1b, _) => ("ESC", 2),
+        _ => ("GS", 2)
+    }
+}
+
+/// Text carried by a buffer, skipping the esc/pos commands and control bytes
+///
+/// Best effort: line feeds are kept, bytes above 127 get decoded as code page 437, and commands split between two buffers are not recognized.
+pub(crate) fn printable_text(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            0x1b | 0x1d => idx += command_length(bytes, idx).1,
+            byte => {
+                if byte == b'\n' || byte >= 0x20 {
+                    text.push(codepage_437::CP437_CONTROL.decode(byte));
+                }
+                idx += 1;
+            }
+        }
+    }
+    text
+}
+
+/// Looks for truncated esc/pos commands in a buffer
+///
+/// Meant as a sanity check for hand-built buffers, before sending them with the [Printer](crate::Printer)'s [raw](crate::Printer::raw) method. The buffer gets scanned for the `ESC` and `GS` commands this library knows about, checking that each one carries all of its arguments (for example, a `GS ( k` missing its length bytes, or an `ESC *` with less data than announced). The check is not exhaustive: unknown commands are skipped, and a missing byte in the middle of the buffer might only be noticed at its end.
+///
+/// ```rust
+/// use escpos_rs::{validate_escpos, ValidationIssue};
+///
+/// // Bit image with two columns of data, in 8 dot mode
+/// let image = vec![0x1b, 0x2a, 0x01, 0x02, 0x00, 0xff, 0x81, b'\n'];
+/// assert_eq!(Ok(()), validate_escpos(&image));
+///
+/// // The same image, with a column missing
+/// let truncated = vec![b'A', 0x1b, 0x2a, 0x01, 0x02, 0x00, 0xff];
+/// assert_eq!(Err(vec![ValidationIssue {
+///     offset: 1,
+///     command: "ESC *",
+///     expected: 7,
+///     found: 6
+/// }]), validate_escpos(&truncated));
+///
+/// // A qr code function, missing its length bytes
+/// assert!(validate_escpos(&[0x1d, 0x28, 0x6b]).is_err());
+/// ```
+pub fn validate_escpos(bytes: &[u8]) -> Result<(), Vec<ValidationIssue>> {
+    let mut issues = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            0x1b | 0x1d => {
+                let (command, expected) = command_length(bytes, idx);
+                let found = bytes.len() - idx;
+                if expected > found {
+                    issues.push(ValidationIssue {
+                        offset: idx,
+                        command,
+                        expected,
+                        found
+                    });
+                }
+                idx += expected;
+            },
+            _ => idx += 1
+        }
+    }
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}